@@ -0,0 +1,170 @@
+use rand::Rng;
+
+use crate::{map_value::MapValue, maze_map::MazeMap, point::Point, random::randarray};
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    fn braid_is_road(&self, p: Point) -> bool {
+        !self.is_overrange(p) && self[p].is_passable()
+    }
+
+    pub fn is_dead_end(&self, p: Point) -> bool {
+        self.braid_is_road(p)
+            && self
+                .neighbours(p)
+                .into_iter()
+                .filter(|&p2| self.braid_is_road(p2))
+                .count()
+                == 1
+    }
+
+    // 打通 p 朝 offset 方向的墙后能到达的道路，墙的两侧必须仍然是墙，保证通道宽度不变
+    fn braid_target(&self, p: Point, offset: Point) -> Option<(Point, Point)> {
+        let wall = self.step(p, offset)?;
+        if self[wall] != MapValue::Wall {
+            return None;
+        }
+        let target = self.step(wall, offset)?;
+        if !self.braid_is_road(target) {
+            return None;
+        }
+        let side = self
+            .neighbours(wall)
+            .into_iter()
+            .filter(|&p2| p2 != p && p2 != target)
+            .any(|p2| self.braid_is_road(p2));
+        if side {
+            return None;
+        }
+        Some((wall, target))
+    }
+
+    // 回溯等生成器的道路不在格子上，没有符合格子布局的墙时，打通 p 旁边任意一面另一侧连着道路的墙
+    // 另一侧有多条道路时优先连接死路，不连接交叉点以免从侧面进入
+    fn braid_fallback(&self, p: Point) -> Vec<(Point, Point)> {
+        self.neighbours(p)
+            .into_iter()
+            .filter(|&wall| self[wall] == MapValue::Wall)
+            .filter_map(|wall| {
+                let targets: Vec<Point> = self
+                    .neighbours(wall)
+                    .into_iter()
+                    .filter(|&p2| {
+                        p2 != p && self.braid_is_road(p2) && self[p2] != MapValue::Crossing
+                    })
+                    .collect();
+                let target = targets
+                    .iter()
+                    .copied()
+                    .find(|&p2| self.is_dead_end(p2))
+                    .or(targets.first().copied())?;
+                Some((wall, target))
+            })
+            .collect()
+    }
+
+    pub fn dead_ends(&self) -> Vec<Point> {
+        let mut res = vec![];
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                if self.is_dead_end(Point(i, j)) {
+                    res.push(Point(i, j));
+                }
+            }
+        }
+        res
+    }
+
+    // 按百分比打通死路形成回路，优先连接到另一条死路
+    pub fn braid(&mut self, percent: u8) {
+        if percent == 0 {
+            return;
+        }
+        let dead_ends = self.dead_ends();
+        let dead_ends = randarray(&mut self.random, &dead_ends);
+        for p in dead_ends {
+            if !self.is_dead_end(p) {
+                continue;
+            }
+            if self.random.gen_range(0..100) >= percent {
+                continue;
+            }
+            let mut walls: Vec<(Point, Point)> = p
+                .get_range_vec()
+                .into_iter()
+                .filter_map(|p2| self.braid_target(p, p2 - p))
+                .collect();
+            // 第 1 版只打通格子之间的墙
+            if walls.is_empty() && self.options.pipeline >= 2 {
+                walls = self.braid_fallback(p);
+            }
+            if walls.is_empty() {
+                continue;
+            }
+            let best: Vec<(Point, Point)> = walls
+                .iter()
+                .copied()
+                .filter(|&(_, target)| self.is_dead_end(target))
+                .collect();
+            let walls = if best.is_empty() { walls } else { best };
+            let (wall, _) = walls[self.random.gen_range(0..walls.len())];
+            self.set(wall, MapValue::Road);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_braid() {
+        // 回溯生成器的道路不在格子上，也要能打通全部死路
+        for generator in [Generator::Kruskal, Generator::default()] {
+            for (row, column) in [(21, 31), (20, 30)] {
+                for seed in 0..5 {
+                    let options = MazeOptions {
+                        generator,
+                        loops: 100,
+                        ..Default::default()
+                    };
+                    let random = StdRng::seed_from_u64(seed);
+                    let maze = MazeMap::new_with_options(row, column, random, options).unwrap();
+                    assert!(
+                        maze.dead_ends().is_empty(),
+                        "{} {} {}",
+                        generator,
+                        row,
+                        seed
+                    );
+                }
+            }
+        }
+
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            loops: 100,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(1);
+        let maze = MazeMap::new_with_options(21, 31, random, options).unwrap();
+
+        // 有回路时 solve 仍然返回最短路径：相邻两步之间不能存在捷径
+        let path = maze.solve(maze.st).unwrap();
+        for (i, a) in path.iter().enumerate() {
+            for b in path.iter().skip(i + 2) {
+                assert!(!a.get_range_vec().contains(b));
+            }
+        }
+
+        let random = StdRng::seed_from_u64(1);
+        let maze = MazeMap::new_with_generator(21, 31, random, Generator::Kruskal).unwrap();
+        assert!(!maze.dead_ends().is_empty());
+    }
+}
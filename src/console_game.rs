@@ -1,14 +1,25 @@
-use clap::Parser;
+use std::{collections::VecDeque, path::PathBuf};
+
+use clap::{Parser, ValueEnum};
 use rand::{thread_rng, Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
 use crate::{
+    difficulty::Difficulty,
     errors::{MazeError, MazeResult},
     game::{Game, GameField, GameValueMap, ToGameValue},
+    generator::{EllerRows, Generator},
+    map_value::MapValue,
+    mask::Mask,
     maze_map::MazeMap,
+    maze_options::{MazeOptions, PIPELINE_VERSION},
     move_status::MoveStatus,
+    placement::Placement,
     player::Player,
     point::Point,
+    solver::Solver,
+    tower::TowerOptions,
+    wrap::Wrap,
 };
 
 #[derive(Debug, Parser)]
@@ -33,30 +44,248 @@ pub enum Cli {
     #[command(about = "new game")]
     New(SubcommandNew),
     #[command(about = "solve the game")]
-    Solve,
+    Solve(SubcommandSolve),
+    #[command(about = "show the next direction on the solution, uses up a hint")]
+    Hint(SubcommandHint),
     #[command(visible_aliases = ["unsolve"], about = "unsolve the game")]
     UnSolve,
     #[command(about = "quit the game")]
     Quit,
     #[command(visible_aliases = ["disp"], about = "display")]
     Display,
+    #[command(about = "scroll down an endless maze")]
+    Descend(SubcommandDescend),
+    #[command(visible_aliases = ["u", "<"], about = "go up the stairs")]
+    Upstairs,
+    #[command(visible_aliases = ["n", ">"], about = "go down the stairs")]
+    Downstairs,
+    #[command(about = "new multi-floor game, only available at start")]
+    Tower(SubcommandTower),
+    #[command(about = "new hex or triangle grid game, only available at start")]
+    Grid(SubcommandGrid),
+    #[command(about = "export the map as text")]
+    Export(SubcommandExport),
+    #[command(about = "print difficulty metrics of the maze")]
+    Stats,
+    #[command(about = "print the code to share this maze, use it with new --code")]
+    Code,
+    #[command(about = "replay the generation of the maze step by step")]
+    AnimateGenerate(SubcommandAnimate),
 }
 
 #[derive(Debug, Parser)]
 pub struct SubcommandNew {
-    pub row: i32,
-    pub column: i32,
+    #[arg(required_unless_present = "code", default_value_t = 0)]
+    pub row: usize,
+    #[arg(required_unless_present = "code", default_value_t = 0)]
+    pub column: usize,
     pub seed: Option<u64>,
+    #[arg(
+        long,
+        conflicts_with_all = ["seed", "algo", "loops", "mask", "wrap", "placement", "frame", "terrain", "target"],
+        help = "regenerate a maze from the code printed by the code command"
+    )]
+    pub code: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "generator: backtracker, prim, kruskal, hunt-and-kill, binary-tree, sidewinder, growing-tree[:newest|oldest|random|middle|mixed=N], wilson, aldous-broder, eller, division[:min=N,bias=N,rooms=N,room-size=N], dungeon[:rooms=N,min=N,max=N,doors=N,prune=BOOL], weave[:density=N]"
+    )]
+    pub algo: Option<Generator>,
+    #[arg(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        help = "percentage of dead ends to remove, 100 for a fully braided maze"
+    )]
+    pub loops: Option<u8>,
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        help = "percentage of roads covered by grass (cost 2), mud (cost 3) or water (cost 5)"
+    )]
+    pub terrain: Option<u8>,
+    #[arg(
+        short,
+        long,
+        help = "ascii art file, space and '.' mark cells outside the maze"
+    )]
+    pub mask: Option<PathBuf>,
+    #[arg(
+        long,
+        conflicts_with = "mask",
+        help = "remove the mask of the last game"
+    )]
+    pub no_mask: bool,
+    #[arg(
+        short,
+        long,
+        help = "wrap the edges: none, cylinder, torus (even rows), mobius (odd rows), columns must be even"
+    )]
+    pub wrap: Option<Wrap>,
+    #[arg(
+        short,
+        long,
+        help = "start and end placement: corner (first and last road in row order), random, border, farthest, fixed:ROW,COL,ROW,COL (inside the frame when --frame is set)"
+    )]
+    pub placement: Option<Placement>,
+    #[arg(
+        long,
+        help = "frame the maze with a border, start and end become openings in it"
+    )]
+    pub frame: bool,
+    #[arg(long, conflicts_with = "frame", help = "remove the border frame")]
+    pub no_frame: bool,
+    #[arg(
+        long,
+        help = "keep drawing seeds until the maze matches, e.g. solution=80..,dead-ends=..0.1,decisions=10..,turns=A..B,tortuosity=A..B"
+    )]
+    pub target: Option<Difficulty>,
+    #[arg(long, default_value_t = 1000, help = "seeds to try for --target")]
+    pub attempts: usize,
 }
 
-enum RunOnceResult {
+impl SubcommandNew {
+    pub fn options(&self, base: &MazeOptions) -> MazeResult<MazeOptions> {
+        let mut options = base.clone();
+        // 从旧的代码载入后，新的迷宫仍然用最新的版本生成
+        options.pipeline = PIPELINE_VERSION;
+        if let Some(algo) = self.algo {
+            options.generator = algo;
+        }
+        if let Some(loops) = self.loops {
+            options.loops = loops;
+        }
+        if let Some(terrain) = self.terrain {
+            options.terrain = terrain;
+        }
+        if let Some(path) = &self.mask {
+            options.mask = Some(Mask::from_file(path)?);
+        }
+        if self.no_mask {
+            options.mask = None;
+        }
+        if let Some(wrap) = self.wrap {
+            options.wrap = wrap;
+        }
+        if let Some(placement) = self.placement {
+            options.placement = placement;
+        }
+        if self.frame {
+            options.frame = true;
+        }
+        if self.no_frame {
+            options.frame = false;
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandTower {
+    pub floors: usize,
+    #[command(flatten)]
+    pub new: SubcommandNew,
+    #[arg(long, help = "stairs between two adjacent floors")]
+    pub stairs: Option<usize>,
+}
+
+impl SubcommandTower {
+    pub fn options(&self, base: &TowerOptions) -> MazeResult<TowerOptions> {
+        if self.new.target.is_some() {
+            return Err(MazeError::Init(String::from("多层迷宫不支持难度条件")));
+        }
+        if self.new.code.is_some() {
+            return Err(MazeError::Init(String::from("多层迷宫不支持迷宫代码")));
+        }
+        let mut options = base.clone();
+        options.maze = self.new.options(&base.maze)?;
+        if let Some(stairs) = self.stairs {
+            options.stairs = stairs;
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GridShape {
+    Hex,
+    Triangle,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandGrid {
+    #[arg(value_enum)]
+    pub shape: GridShape,
+    pub row: usize,
+    pub column: usize,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandExport {
+    #[arg(help = "output file, print to the console if omitted")]
+    pub path: Option<PathBuf>,
+    #[arg(long, help = "export with the border frame")]
+    pub frame: bool,
+    #[arg(
+        long,
+        conflicts_with = "frame",
+        help = "export without the border frame"
+    )]
+    pub no_frame: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandAnimate {
+    #[arg(
+        short,
+        long,
+        default_value_t = 100,
+        help = "events per second, 0 for no delay"
+    )]
+    pub speed: u32,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandSolve {
+    #[arg(
+        short,
+        long,
+        help = "solver: bfs, astar, bidirectional, dead-end-filling, dijkstra (by terrain cost), wall-follower[:left|right], tremaux"
+    )]
+    pub algo: Option<Solver>,
+    #[arg(long, help = "show the cells explored by the solver")]
+    pub explored: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandHint {
+    #[arg(long, help = "show the next junction and the direction to take there")]
+    pub junction: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct SubcommandDescend {
+    #[arg(default_value_t = 2, help = "rows to scroll")]
+    pub rows: usize,
+}
+
+// 每局可以使用的提示次数和每次提示加到分数上的罚分
+pub const HINT_BUDGET: usize = 3;
+pub const HINT_PENALTY: i32 = 10;
+
+pub(crate) enum RunOnceResult {
     Ok,
     InValid,
     CanNotMove,
     Quit,
     Error(String),
     CmdError(String),
+    // 命令成功时需要显示的信息
+    Message(String),
     Display,
+    Descend,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -69,27 +298,23 @@ where
     is_move: bool,
     value_map: GameValueMap<String>,
     will_solve: bool,
+    solver: Solver,
+    show_explored: bool,
+    hint_budget: usize,
     solve_list: Option<Vec<Point>>,
     move_list: Option<Vec<Point>>,
+    descend: Option<EllerRows<R>>,
+    descend_rows: VecDeque<Vec<MapValue>>,
+    descend_depth: usize,
 }
 
 impl ConsoleGame<ChaCha8Rng> {
-    pub fn new(row: i32, column: i32) -> MazeResult<Self> {
+    pub fn new(row: usize, column: usize) -> MazeResult<Self> {
         let random = match ChaCha8Rng::from_rng(thread_rng()) {
             Ok(r) => r,
             Err(e) => return Err(MazeError::Init(e.to_string())),
         };
-        let map = MazeMap::new_with_random(row, column, random)?;
-        let player = Player::new(map.st, "player");
-        Ok(Self {
-            map,
-            player,
-            is_move: false,
-            value_map: Self::new_value_map(),
-            will_solve: false,
-            solve_list: None,
-            move_list: None,
-        })
+        Self::new_with_random(row, column, random)
     }
 }
 
@@ -97,38 +322,167 @@ impl<R> ConsoleGame<R>
 where
     R: Rng + SeedableRng,
 {
-    fn new_value_map() -> GameValueMap<String> {
+    pub(crate) fn new_value_map() -> GameValueMap<String> {
         GameValueMap {
-            empty: "?".to_owned(),
+            empty: " ".to_owned(),
             r#move: ".".to_owned(),
             solve: "#".to_owned(),
+            explored: ":".to_owned(),
             wall: "O".to_owned(),
             road: " ".to_owned(),
+            grass: ",".to_owned(),
+            mud: "%".to_owned(),
+            water: "~".to_owned(),
+            crossing: "+".to_owned(),
             border: "O".to_owned(),
+            stairs_up: "<".to_owned(),
+            stairs_down: ">".to_owned(),
             player: "P".to_owned(),
             st: "S".to_owned(),
             ed: "E".to_owned(),
         }
     }
 
-    pub fn new_with_random(row: i32, column: i32, random: R) -> MazeResult<Self> {
-        let map = MazeMap::new_with_random(row, column, random)?;
+    pub fn new_with_random(row: usize, column: usize, random: R) -> MazeResult<Self> {
+        Self::new_with_options(row, column, random, MazeOptions::default())
+    }
+
+    pub fn new_with_generator(
+        row: usize,
+        column: usize,
+        random: R,
+        generator: Generator,
+    ) -> MazeResult<Self> {
+        Self::new_with_options(row, column, random, MazeOptions::new(generator))
+    }
+
+    pub fn new_with_options(
+        row: usize,
+        column: usize,
+        random: R,
+        options: MazeOptions,
+    ) -> MazeResult<Self> {
+        let map = MazeMap::new_with_options(row, column, random, options)?;
+        Ok(Self::from_map(map))
+    }
+
+    pub fn new_with_seed(
+        row: usize,
+        column: usize,
+        seed: u64,
+        options: MazeOptions,
+    ) -> MazeResult<Self> {
+        let map = MazeMap::new_with_seed(row, column, seed, options)?;
+        Ok(Self::from_map(map))
+    }
+
+    pub fn from_code(code: &str) -> MazeResult<Self> {
+        Ok(Self::from_map(MazeMap::from_code(code)?))
+    }
+
+    // 返回满足难度条件的游戏和迷宫的种子
+    pub fn new_with_difficulty(
+        row: usize,
+        column: usize,
+        seed: u64,
+        options: MazeOptions,
+        difficulty: &Difficulty,
+        attempts: usize,
+    ) -> MazeResult<(Self, u64)> {
+        let (map, seed) =
+            MazeMap::new_with_difficulty(row, column, seed, options, difficulty, attempts)?;
+        Ok((Self::from_map(map), seed))
+    }
+
+    fn from_map(map: MazeMap<R>) -> Self {
         let player = Player::new(map.st, "player");
-        Ok(Self {
+        Self {
             map,
             player,
             is_move: false,
             value_map: Self::new_value_map(),
             will_solve: false,
+            solver: Solver::default(),
+            show_explored: false,
+            hint_budget: HINT_BUDGET,
             solve_list: None,
             move_list: None,
-        })
+            descend: None,
+            descend_rows: VecDeque::new(),
+            descend_depth: 0,
+        }
     }
 
     fn reset(&mut self) {
         self.is_move = false;
         self.move_list = None;
         self.solve_list = None;
+        self.descend = None;
+        self.descend_rows.clear();
+        self.descend_depth = 0;
+    }
+
+    // 无尽下降模式：窗口高度与当前地图一致，每次向下滚动若干行
+    pub fn descend(&mut self, rows: usize) -> &VecDeque<Vec<MapValue>> {
+        let (height, column) = (self.map.row(), self.map.column());
+        if self.descend.is_none() {
+            let random = R::seed_from_u64(self.map.random.gen());
+            self.descend = Some(EllerRows::new(column, random));
+        }
+        let descend = self.descend.as_mut().unwrap();
+        let need = if self.descend_rows.is_empty() {
+            height
+        } else {
+            rows
+        };
+        for line in descend.by_ref().take(need) {
+            self.descend_rows.push_back(line);
+            if self.descend_rows.len() > height {
+                self.descend_rows.pop_front();
+                self.descend_depth += 1;
+            }
+        }
+        &self.descend_rows
+    }
+
+    fn display_descend(&self) {
+        let mut lines = vec![format!("depth: {}", self.descend_depth)];
+        for line in self.descend_rows.iter() {
+            lines.push(
+                line.iter()
+                    .map(|value| value.to(&self.value_map).as_str())
+                    .collect::<Vec<&str>>()
+                    .join(""),
+            );
+        }
+        println!("{}", lines.join("\n"));
+    }
+
+    pub fn set_hint_budget(&mut self, budget: usize) {
+        self.hint_budget = budget;
+    }
+
+    pub fn hints_left(&self) -> usize {
+        self.hint_budget.saturating_sub(self.player.hints.len())
+    }
+
+    // 用掉一次提示，返回要显示的提示
+    pub fn use_hint(&mut self, junction: bool) -> MazeResult<String> {
+        if self.hints_left() == 0 {
+            return Err(MazeError::NoHintsLeft);
+        }
+        let msg = if junction {
+            let (p, status) = self.hint_junction()?;
+            if p == self.player.pos {
+                format!("hint: go {} here", status)
+            } else {
+                format!("hint: go {} at the junction {}", status, p)
+            }
+        } else {
+            format!("hint: go {}", self.hint()?)
+        };
+        self.player.record_hint(HINT_PENALTY);
+        Ok(format!("{}, hints left: {}", msg, self.hints_left()))
     }
 
     pub fn restart(&mut self) {
@@ -137,19 +491,94 @@ where
         self.solve_list = None;
     }
 
-    pub fn new_game(&mut self, row: i32, column: i32) -> MazeResult<()> {
+    // 重新生成当前大小的迷宫并逐步显示生成过程，知道种子时生成的还是当前的迷宫
+    // speed 为每秒的事件数，0 表示不等待
+    pub fn animate_generate(&mut self, speed: u32) -> MazeResult<()> {
+        let (row, column) = (self.map.row(), self.map.column());
+        let seed = self.map.seed;
+        if let Some(seed) = seed {
+            self.map.random = R::seed_from_u64(seed);
+        }
+        let record = self.map.generate_with_events(row, column)?;
+        self.map.seed = seed;
+        self.player = Player::new(self.map.st, "player");
+        self.reset();
+
+        // 速度很快时几个事件合成一帧，每秒最多刷新 60 次
+        let batch = (speed as usize / 60).max(1);
+        let delay = match speed {
+            0 => std::time::Duration::ZERO,
+            speed => std::time::Duration::from_secs_f64(batch as f64 / speed as f64),
+        };
+        let mut replay = record.replay();
+        let mut count = 0;
+        while let Some(event) = replay.next() {
+            count += 1;
+            if count % batch != 0 && count != record.events.len() {
+                continue;
+            }
+            let mut lines: Vec<String> = replay
+                .map
+                .rows()
+                .map(|line| {
+                    line.iter()
+                        .map(|value| value.to(&self.value_map).as_str())
+                        .collect()
+                })
+                .collect();
+            let p = event.point();
+            let (i, j) = (p.0 as usize, p.1 as usize);
+            lines[i].replace_range(j..j + 1, &self.value_map.solve);
+            // 清屏后回到左上角
+            print!("\x1b[2J\x1b[H");
+            println!("event {}/{}: {:?}", count, record.events.len(), event);
+            println!("{}", lines.join("\n"));
+            std::thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    // 不指定时按当前地图是否有边框导出
+    pub fn export(&self, with_frame: Option<bool>) -> MazeResult<String> {
+        let with_frame = with_frame.unwrap_or(self.map.is_framed());
+        let map = self.map.export(with_frame)?;
+        let lines: Vec<String> = map
+            .rows()
+            .map(|line| {
+                line.iter()
+                    .map(|value| value.to(&self.value_map).as_str())
+                    .collect()
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+
+    pub fn new_game(&mut self, row: usize, column: usize) -> MazeResult<()> {
         self.map.generate(row, column)?;
         self.player = Player::new(self.map.st, "player");
         self.reset();
         Ok(())
     }
 
-    pub fn new_game_with_random(&mut self, row: i32, column: i32, random: R) -> MazeResult<()> {
+    pub fn new_game_with_random(&mut self, row: usize, column: usize, random: R) -> MazeResult<()> {
         self.map.generate_with_new_random(row, column, random)?;
         self.player = Player::new(self.map.st, "player");
         self.reset();
         Ok(())
     }
+
+    pub fn new_game_with_seed(&mut self, row: usize, column: usize, seed: u64) -> MazeResult<()> {
+        self.map.generate_with_seed(row, column, seed)?;
+        self.player = Player::new(self.map.st, "player");
+        self.reset();
+        Ok(())
+    }
+
+    fn replace_map(&mut self, map: MazeMap<R>) {
+        self.map = map;
+        self.player = Player::new(self.map.st, "player");
+        self.reset();
+    }
 }
 
 impl<R> GameField<R, String> for ConsoleGame<R>
@@ -173,26 +602,58 @@ where
         let player_name_str = format!("player name: {}", self.player.name);
         let step_str = format!("step: {}", self.player.step);
         let move_times_str = format!("move times: {}", self.player.move_times);
+        let score_str = format!(
+            "score: {}, hints left: {}",
+            self.player.score(),
+            self.hints_left()
+        );
         let mut res_list = vec![
             vec![&player_name_str],
             vec![&step_str],
             vec![&move_times_str],
+            vec![&score_str],
         ];
         let mut map_list: Vec<Vec<&String>> = self
             .map
-            .map
-            .iter()
+            .map()
+            .rows()
             .map(|line| line.iter().map(|value| value.to(&self.value_map)).collect())
             .collect();
         // solve
-        let solve_list;
-        if self.will_solve {
-            solve_list = self.solve(self.player.pos)?;
-        } else {
-            solve_list = vec![];
+        // 沿墙走在有回路的迷宫中可能找不到终点，只显示错误
+        // 默认的求解器不显示搜索过的位置时直接用缓存的解，重绘时不用重新搜索
+        let mut solver_str = None;
+        if self.will_solve && self.solver == Solver::Bfs && !self.show_explored {
+            let path = self.solve(self.player.pos)?;
+            for &p in path.iter() {
+                map_list[p] = &self.value_map.solve;
+            }
+        } else if self.will_solve {
+            match self
+                .map
+                .solve_by(&self.solver, self.player.pos, self.map.ed)
+            {
+                Ok(solution) => {
+                    if self.show_explored {
+                        for &p in solution.order.iter() {
+                            map_list[p] = &self.value_map.explored;
+                        }
+                    }
+                    for &p in solution.path.iter() {
+                        map_list[p] = &self.value_map.solve;
+                    }
+                    solver_str = Some(format!(
+                        "solver: {}, explored: {}, cost: {}",
+                        self.solver,
+                        solution.explored().len(),
+                        self.map.path_cost(&solution.path)
+                    ));
+                }
+                Err(e) => solver_str = Some(format!("solver: {}, {}", self.solver, e)),
+            }
         }
-        for p in solve_list {
-            map_list[p] = &self.value_map.solve;
+        if let Some(solver_str) = &solver_str {
+            res_list.push(vec![solver_str]);
         }
         // move
         if let Some(move_list) = &self.move_list {
@@ -202,6 +663,11 @@ where
         }
         // player
         map_list[self.player.pos] = &self.value_map.player;
+        // wrap
+        let labels: Vec<String> = ('a'..='z').map(String::from).collect();
+        if self.map.options.wrap != Wrap::None {
+            map_list = self.wrap_frame(map_list, &labels);
+        }
 
         res_list.extend(map_list);
         let res_string = res_list
@@ -224,7 +690,7 @@ where
         move_list: Vec<crate::point::Point>,
         step: i32,
     ) -> MazeResult<Vec<crate::point::Point>> {
-        self.player.pos = move_list[step as usize];
+        self.player.pos = *move_list.last().unwrap();
         self.player.step += step;
         self.player.move_times += 1;
 
@@ -243,28 +709,56 @@ where
         loop {
             let mut buf = String::new();
             std::io::stdin().read_line(&mut buf).unwrap_or_else(|e| {
-                println!("{}", e.to_string());
+                println!("{}", e);
                 0
             });
             self.move_list = None;
             match self.run_once(buf.trim()) {
-                RunOnceResult::Ok => self
-                    .display()
-                    .unwrap_or_else(|e| println!("{}", e.to_string())),
+                RunOnceResult::Ok => self.display().unwrap_or_else(|e| println!("{}", e)),
                 RunOnceResult::InValid => println!("invalid input"),
                 RunOnceResult::CanNotMove => println!("can not move"),
                 RunOnceResult::Quit => break,
                 RunOnceResult::Error(err) => println!("error: {}", err),
                 RunOnceResult::CmdError(err) => println!("{}", err),
-                RunOnceResult::Display => self
-                    .display()
-                    .unwrap_or_else(|e| println!("{}", e.to_string())),
+                RunOnceResult::Message(msg) => println!("{}", msg),
+                RunOnceResult::Display => self.display().unwrap_or_else(|e| println!("{}", e)),
+                RunOnceResult::Descend => self.display_descend(),
             }
         }
         Ok(())
     }
 }
 
+impl<R> ConsoleGame<R>
+where
+    R: Rng,
+{
+    // 环绕时在地图外围加一圈，用相同的字母标出通道离开和重新进入的位置
+    fn wrap_frame<'a>(
+        &'a self,
+        map_list: Vec<Vec<&'a String>>,
+        labels: &'a [String],
+    ) -> Vec<Vec<&'a String>> {
+        let (row, column) = (self.map.row(), self.map.column());
+        let mut frame = vec![vec![&self.value_map.empty; column + 2]; row + 2];
+        for (i, line) in map_list.into_iter().enumerate() {
+            for (j, value) in line.into_iter().enumerate() {
+                frame[i + 1][j + 1] = value;
+            }
+        }
+        for (k, (out, back)) in self.map.seams().into_iter().enumerate() {
+            for p in [out, back] {
+                frame[p + (1, 1)] = &labels[k % labels.len()];
+            }
+        }
+        if !self.map.options.wrap.is_vertical() {
+            frame.remove(0);
+            frame.pop();
+        }
+        frame
+    }
+}
+
 // run
 impl<R> ConsoleGame<R>
 where
@@ -302,22 +796,101 @@ where
                 return RunOnceResult::Ok;
             }
             Cli::New(sub) => {
-                if let Some(state) = sub.seed {
-                    match self.new_game_with_random(sub.row, sub.column, R::seed_from_u64(state)) {
-                        Ok(_) => {}
+                if let Some(code) = &sub.code {
+                    match MazeMap::from_code(code) {
+                        Ok(map) => self.replace_map(map),
                         Err(e) => return RunOnceResult::Error(e.to_string()),
-                    };
-                } else {
-                    match self.new_game(sub.row, sub.column) {
-                        Ok(_) => {}
+                    }
+                    return RunOnceResult::Ok;
+                }
+                self.map.options = match sub.options(&self.map.options) {
+                    Ok(options) => options,
+                    Err(e) => return RunOnceResult::Error(e.to_string()),
+                };
+                // 总是用种子生成，新的迷宫都可以用 `code` 分享
+                let seed = sub.seed.unwrap_or_else(|| self.map.random.gen());
+                if let Some(target) = &sub.target {
+                    let options = self.map.options.clone();
+                    match MazeMap::new_with_difficulty(
+                        sub.row,
+                        sub.column,
+                        seed,
+                        options,
+                        target,
+                        sub.attempts,
+                    ) {
+                        Ok((map, seed)) => {
+                            self.replace_map(map);
+                            println!("seed: {}", seed);
+                        }
                         Err(e) => return RunOnceResult::Error(e.to_string()),
                     }
+                } else if let Err(e) = self.new_game_with_seed(sub.row, sub.column, seed) {
+                    return RunOnceResult::Error(e.to_string());
+                }
+            }
+            Cli::Solve(sub) => {
+                if let Some(solver) = sub.algo {
+                    self.solver = solver;
+                }
+                self.show_explored = sub.explored;
+                self.will_solve = true;
+            }
+            Cli::Hint(sub) => {
+                return match self.use_hint(sub.junction) {
+                    Ok(msg) => RunOnceResult::Message(msg),
+                    Err(e @ MazeError::NoHintsLeft) => RunOnceResult::CmdError(e.to_string()),
+                    Err(e) => RunOnceResult::Error(e.to_string()),
                 }
             }
-            Cli::Solve => self.will_solve = true,
             Cli::UnSolve => self.will_solve = false,
             Cli::Quit => return RunOnceResult::Quit,
             Cli::Display => return RunOnceResult::Display,
+            Cli::Descend(sub) => {
+                self.descend(sub.rows);
+                return RunOnceResult::Descend;
+            }
+            Cli::Upstairs => return self.inner_move(MoveStatus::Upstairs),
+            Cli::Downstairs => return self.inner_move(MoveStatus::Downstairs),
+            Cli::Tower(_) | Cli::Grid(_) => {
+                return RunOnceResult::CmdError(String::from("only available at start"))
+            }
+            Cli::Export(sub) => {
+                let with_frame = match (sub.frame, sub.no_frame) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+                let text = match self.export(with_frame) {
+                    Ok(text) => text,
+                    Err(e) => return RunOnceResult::Error(e.to_string()),
+                };
+                let msg = match sub.path {
+                    Some(path) => match std::fs::write(&path, text + "\n") {
+                        Ok(_) => format!("exported to {}", path.display()),
+                        Err(e) => return RunOnceResult::Error(e.to_string()),
+                    },
+                    None => text,
+                };
+                return RunOnceResult::Message(msg);
+            }
+            Cli::AnimateGenerate(sub) => {
+                if let Err(e) = self.animate_generate(sub.speed) {
+                    return RunOnceResult::Error(e.to_string());
+                }
+            }
+            Cli::Code => {
+                return match self.map.to_code() {
+                    Ok(code) => RunOnceResult::Message(code),
+                    Err(e) => RunOnceResult::Error(e.to_string()),
+                }
+            }
+            Cli::Stats => {
+                return match self.map.stats() {
+                    Ok(stats) => RunOnceResult::Message(stats.to_string()),
+                    Err(e) => RunOnceResult::Error(e.to_string()),
+                }
+            }
         };
         RunOnceResult::Ok
     }
@@ -328,6 +901,8 @@ mod tests {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
 
+    use crate::solver::Hand;
+
     use super::*;
 
     #[test]
@@ -348,5 +923,167 @@ mod tests {
         game.move_to(MoveStatus::Down).unwrap();
         game.move_to(MoveStatus::Down).unwrap();
         assert!(game.is_win().unwrap());
+        assert!(matches!(
+            game.run_once("stats"),
+            RunOnceResult::Message(stats) if stats.starts_with("solution length")
+        ));
+
+        // 不知道种子时无法生成代码
+        assert!(matches!(game.run_once("code"), RunOnceResult::Error(_)));
+        game.run_once("new 11 21 5");
+        let RunOnceResult::Message(code) = game.run_once("code") else {
+            panic!("code of a seeded maze");
+        };
+        let map = game.map.map().clone();
+        game.run_once(&format!("new --code {}", code));
+        assert_eq!(game.map.map(), &map);
+    }
+
+    #[test]
+    fn test_move_through_crossing() {
+        let options = MazeOptions::new("weave:density=100".parse().unwrap());
+        let random = ChaCha8Rng::seed_from_u64(1);
+        let mut game = ConsoleGame::new_with_options(21, 31, random, options).unwrap();
+        let crossing = (0..game.row() as i32)
+            .flat_map(|i| (0..game.column() as i32).map(move |j| Point(i, j)))
+            .find(|&p| game.map[p] == MapValue::Crossing)
+            .unwrap();
+        game.move_player(crossing - (2, 0)).unwrap();
+        let move_list = game.move_to(MoveStatus::Down).unwrap();
+        assert_eq!(move_list[..4], [0, 1, 2, 3].map(|i| crossing + (i - 2, 0)));
+    }
+
+    #[test]
+    fn test_animate_generate() {
+        let random = ChaCha8Rng::seed_from_u64(1);
+        let mut game = ConsoleGame::new_with_seed(7, 9, 5, MazeOptions::default()).unwrap();
+        let map = game.map.map().clone();
+        game.animate_generate(0).unwrap();
+        // 知道种子时重放的就是当前的迷宫
+        assert_eq!((game.map.map().clone(), game.map.seed), (map, Some(5)));
+        game = ConsoleGame::new_with_random(7, 9, random).unwrap();
+        game.animate_generate(0).unwrap();
+        assert!(game.solve(game.map.st).is_ok());
+    }
+
+    #[test]
+    fn test_solve_algo() {
+        let random = ChaCha8Rng::seed_from_u64(1);
+        let mut game = ConsoleGame::new_with_random(11, 21, random).unwrap();
+        assert!(matches!(
+            game.run_once("solve --algo wall-follower:right --explored"),
+            RunOnceResult::Ok
+        ));
+        assert_eq!(game.solver, Solver::WallFollower(Hand::Right));
+        assert!(game.will_solve && game.show_explored);
+        assert!(matches!(
+            game.run_once("solve --algo dfs"),
+            RunOnceResult::CmdError(_)
+        ));
+        // 不指定求解器时保留之前的选择
+        game.run_once("solve");
+        assert_eq!(game.solver, Solver::WallFollower(Hand::Right));
+        assert!(!game.show_explored);
+        game.display().unwrap();
+    }
+
+    #[test]
+    fn test_terrain_step() {
+        let options = MazeOptions {
+            terrain: 50,
+            ..Default::default()
+        };
+        let random = ChaCha8Rng::seed_from_u64(2);
+        let mut game = ConsoleGame::new_with_options(15, 25, random, options).unwrap();
+        let directions = [
+            MoveStatus::Up,
+            MoveStatus::Down,
+            MoveStatus::Left,
+            MoveStatus::Right,
+        ];
+        // 沿解走到终点，步数等于走过的地形代价之和
+        let mut walked = vec![game.player.pos];
+        while !game.is_win().unwrap() {
+            let path = game.solve(game.player.pos).unwrap();
+            let status = directions
+                .into_iter()
+                .find(|status| status.get_next(path[0]) == path[1])
+                .unwrap();
+            let move_list = game.move_to(status).unwrap();
+            walked.extend(move_list.into_iter().skip(1));
+        }
+        assert_eq!(game.player.step as usize, game.map.path_cost(&walked));
+        assert!(game.player.step as usize > walked.len() - 1);
+    }
+
+    #[test]
+    fn test_hint() {
+        let random = ChaCha8Rng::seed_from_u64(7);
+        let mut game = ConsoleGame::new_with_random(15, 25, random).unwrap();
+        let (junction, status) = game.hint_junction().unwrap();
+        let path = game.solve(game.player.pos).unwrap();
+        let i = path.iter().position(|&p| p == junction).unwrap();
+        assert_eq!(status.get_next(junction), path[i + 1]);
+        // 一直按提示走可以走到终点
+        while !game.is_win().unwrap() {
+            let status = game.hint().unwrap();
+            game.move_to(status).unwrap();
+        }
+        assert_eq!(game.hint(), Err(MazeError::GameWin));
+        let step = game.player.step;
+
+        game.restart();
+        for _ in 0..HINT_BUDGET {
+            assert!(matches!(game.run_once("hint"), RunOnceResult::Message(_)));
+        }
+        assert!(matches!(game.run_once("hint"), RunOnceResult::CmdError(_)));
+        assert_eq!(game.use_hint(true), Err(MazeError::NoHintsLeft));
+        assert_eq!(game.player.hints, vec![game.map.st; HINT_BUDGET]);
+        assert_eq!(
+            game.player.score(),
+            step + HINT_PENALTY * HINT_BUDGET as i32
+        );
+        game.new_game(15, 25).unwrap();
+        assert_eq!(game.hints_left(), HINT_BUDGET);
+    }
+
+    #[test]
+    fn test_frame() {
+        let options = MazeOptions {
+            frame: true,
+            ..Default::default()
+        };
+        let random = ChaCha8Rng::seed_from_u64(1);
+        let mut game = ConsoleGame::new_with_options(12, 22, random, options).unwrap();
+        // 从边框上的入口沿着解一路走到出口
+        let directions = [
+            MoveStatus::Up,
+            MoveStatus::Down,
+            MoveStatus::Left,
+            MoveStatus::Right,
+        ];
+        while !game.is_win().unwrap() {
+            let path = game.solve(game.player.pos).unwrap();
+            let status = directions
+                .into_iter()
+                .find(|status| status.get_next(path[0]) == path[1])
+                .unwrap();
+            game.move_to(status).unwrap();
+        }
+
+        let framed = game.export(None).unwrap();
+        let lines: Vec<&str> = framed.lines().collect();
+        assert_eq!((lines.len(), lines[0].len()), (12, 22));
+        assert_eq!(framed.matches('S').count() + framed.matches('E').count(), 2);
+        let inner = game.export(Some(false)).unwrap();
+        assert_eq!(inner.lines().count(), 10);
+        assert!(matches!(
+            game.run_once("export --no-frame"),
+            RunOnceResult::Message(text) if text == inner
+        ));
+        assert!(matches!(
+            game.run_once("export /nonexistent/maze.txt"),
+            RunOnceResult::Error(_)
+        ));
     }
 }
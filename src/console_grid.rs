@@ -0,0 +1,187 @@
+use clap::Parser;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    console_game::{Cli, RunOnceResult},
+    errors::{MazeError, MazeResult},
+    grid_maze::GridMaze,
+    player::Player,
+    point::Point,
+    topology::Topology,
+};
+
+// 六边形和三角形网格的控制台游戏，输入方向名移动，例如 `ne`、`sw`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConsoleGrid<T, R = ChaCha8Rng>
+where
+    T: Topology,
+    R: Rng,
+{
+    map: GridMaze<T, R>,
+    player: Player,
+    will_solve: bool,
+    move_list: Option<Vec<Point>>,
+    directions: String,
+}
+
+impl<T, R> ConsoleGrid<T, R>
+where
+    T: Topology,
+    R: Rng + SeedableRng,
+{
+    pub fn new_with_random(
+        row: usize,
+        column: usize,
+        random: R,
+        directions: &str,
+    ) -> MazeResult<Self> {
+        let map = GridMaze::new_with_random(row, column, random)?;
+        let player = Player::new(map.st, "player");
+        Ok(Self {
+            map,
+            player,
+            will_solve: false,
+            move_list: None,
+            directions: directions.to_owned(),
+        })
+    }
+
+    pub fn map(&self) -> &GridMaze<T, R> {
+        &self.map
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn is_win(&self) -> bool {
+        self.player.pos == self.map.ed
+    }
+
+    pub fn restart(&mut self) {
+        self.player.pos = self.map.st;
+        self.move_list = None;
+    }
+
+    pub fn move_to(&mut self, dir: T::Direction) -> MazeResult<Vec<Point>> {
+        if self.is_win() {
+            return Err(MazeError::GameWin);
+        }
+        let move_list = self.map.move_from(self.player.pos, dir)?;
+        self.player.pos = *move_list.last().unwrap();
+        self.player.step += move_list.len() as i32 - 1;
+        self.player.move_times += 1;
+        Ok(move_list)
+    }
+
+    pub fn display(&self) -> MazeResult<()> {
+        let solve_list = if self.will_solve {
+            self.map.solve(self.player.pos)?
+        } else {
+            vec![]
+        };
+        let move_list = self.move_list.clone().unwrap_or_default();
+        let lines = self.map.render(|p| {
+            if p == self.player.pos {
+                'P'
+            } else if move_list.contains(&p) {
+                '.'
+            } else if p == self.map.st {
+                'S'
+            } else if p == self.map.ed {
+                'E'
+            } else if solve_list.contains(&p) {
+                '#'
+            } else {
+                ' '
+            }
+        });
+        let mut res_list = vec![
+            format!("player name: {}", self.player.name),
+            format!("step: {}", self.player.step),
+            format!("move times: {}", self.player.move_times),
+            format!("directions: {}", self.directions),
+        ];
+        res_list.extend(lines);
+        println!("{}", res_list.join("\n"));
+        Ok(())
+    }
+
+    fn run_once(&mut self, cmd: &str) -> RunOnceResult {
+        if let Ok(dir) = cmd.parse::<T::Direction>() {
+            return match self.move_to(dir) {
+                Ok(move_list) => {
+                    self.move_list = Some(move_list);
+                    RunOnceResult::Ok
+                }
+                Err(MazeError::CanNotMove) => RunOnceResult::CanNotMove,
+                Err(err) => RunOnceResult::Error(err.to_string()),
+            };
+        }
+        let itr = match shlex::split(cmd) {
+            Some(itr) => itr,
+            None => return RunOnceResult::InValid,
+        };
+        let cli = match Cli::try_parse_from(itr) {
+            Ok(cli) => cli,
+            Err(e) => return RunOnceResult::CmdError(e.to_string()),
+        };
+        match cli {
+            Cli::Restart => self.restart(),
+            Cli::New(sub) => {
+                if let Some(state) = sub.seed {
+                    self.map.random = R::seed_from_u64(state);
+                }
+                if let Err(e) = self.map.generate(sub.row, sub.column) {
+                    return RunOnceResult::Error(e.to_string());
+                }
+                self.player = Player::new(self.map.st, "player");
+                self.restart();
+            }
+            Cli::Solve(sub) => {
+                if sub.algo.is_some() || sub.explored {
+                    return RunOnceResult::CmdError(String::from("only bfs is available here"));
+                }
+                self.will_solve = true;
+            }
+            Cli::UnSolve => self.will_solve = false,
+            Cli::Quit => return RunOnceResult::Quit,
+            Cli::Display => return RunOnceResult::Display,
+            _ => {
+                let msg = format!(
+                    "not available in grid mode, directions: {}",
+                    self.directions
+                );
+                return RunOnceResult::CmdError(msg);
+            }
+        };
+        RunOnceResult::Ok
+    }
+
+    pub fn run(&mut self) -> MazeResult<()> {
+        println!("game start!");
+        self.display().unwrap();
+        loop {
+            let mut buf = String::new();
+            std::io::stdin().read_line(&mut buf).unwrap_or_else(|e| {
+                println!("{}", e);
+                0
+            });
+            self.move_list = None;
+            match self.run_once(buf.trim()) {
+                RunOnceResult::Ok | RunOnceResult::Display => {
+                    self.display().unwrap_or_else(|e| println!("{}", e))
+                }
+                RunOnceResult::InValid => println!("invalid input"),
+                RunOnceResult::CanNotMove => println!("can not move"),
+                RunOnceResult::Quit => break,
+                RunOnceResult::Error(err) => println!("error: {}", err),
+                RunOnceResult::CmdError(err) => println!("{}", err),
+                RunOnceResult::Message(msg) => println!("{}", msg),
+                RunOnceResult::Descend => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+use clap::Parser;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    console_game::{Cli, ConsoleGame, RunOnceResult},
+    errors::{MazeError, MazeResult},
+    game::{GameValueMap, ToGameValue},
+    map_value::MapValue,
+    move_status::MoveStatus,
+    player::Player,
+    tower::{TowerMap, TowerOptions, TowerPoint},
+};
+
+// 多层迷宫的控制台游戏，每次只显示玩家所在的楼层
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConsoleTower<R = ChaCha8Rng>
+where
+    R: Rng,
+{
+    map: TowerMap<R>,
+    player: Player,
+    floor: usize,
+    value_map: GameValueMap<String>,
+    will_solve: bool,
+    move_list: Option<Vec<TowerPoint>>,
+}
+
+impl<R> ConsoleTower<R>
+where
+    R: Rng + SeedableRng,
+{
+    pub fn new_with_options(
+        floors: usize,
+        row: usize,
+        column: usize,
+        random: R,
+        options: TowerOptions,
+    ) -> MazeResult<Self> {
+        let map = TowerMap::new_with_options(floors, row, column, random, options)?;
+        let player = Player::new(map.st.1, "player");
+        Ok(Self {
+            floor: map.st.0,
+            map,
+            player,
+            value_map: ConsoleGame::<R>::new_value_map(),
+            will_solve: false,
+            move_list: None,
+        })
+    }
+
+    pub fn map(&self) -> &TowerMap<R> {
+        &self.map
+    }
+
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn pos(&self) -> TowerPoint {
+        TowerPoint(self.floor, self.player.pos)
+    }
+
+    pub fn is_win(&self) -> bool {
+        self.pos() == self.map.ed
+    }
+
+    pub fn restart(&mut self) {
+        self.floor = self.map.st.0;
+        self.player.pos = self.map.st.1;
+        self.move_list = None;
+    }
+
+    pub fn move_to(&mut self, r#move: MoveStatus) -> MazeResult<Vec<TowerPoint>> {
+        if self.is_win() {
+            return Err(MazeError::GameWin);
+        }
+        let move_list = self.map.move_from(self.pos(), r#move)?;
+        let last = *move_list.last().unwrap();
+        self.floor = last.0;
+        self.player.pos = last.1;
+        let cost: usize = move_list.iter().skip(1).map(|&p| self.map[p].cost()).sum();
+        self.player.step += cost as i32;
+        self.player.move_times += 1;
+        Ok(move_list)
+    }
+
+    fn stairs_str(&self) -> String {
+        let floor = self.map.floor(self.floor);
+        let mut kinds = vec![];
+        for (value, name) in [(MapValue::StairsUp, "up"), (MapValue::StairsDown, "down")] {
+            if floor.iter().any(|&v| v == value) {
+                kinds.push(format!("{} {}", value.to(&self.value_map), name));
+            }
+        }
+        let here = match self.map[self.pos()] {
+            MapValue::StairsUp => ", standing on stairs up",
+            MapValue::StairsDown => ", standing on stairs down",
+            _ => "",
+        };
+        format!("stairs: {}{}", kinds.join(", "), here)
+    }
+
+    pub fn display(&self) -> MazeResult<()> {
+        let mut lines = vec![
+            format!("player name: {}", self.player.name),
+            format!("step: {}", self.player.step),
+            format!("move times: {}", self.player.move_times),
+            format!("floor: {}/{}", self.floor + 1, self.map.floor_count()),
+            self.stairs_str(),
+        ];
+        let mut map_list: Vec<Vec<&String>> = self
+            .map
+            .floor(self.floor)
+            .rows()
+            .map(|line| line.iter().map(|value| value.to(&self.value_map)).collect())
+            .collect();
+        // solve
+        let solve_list = if self.will_solve {
+            self.map.solve(self.pos())?
+        } else {
+            vec![]
+        };
+        // 楼梯保持原样，方便看出路线在哪里换层
+        for p in solve_list {
+            if p.0 == self.floor
+                && !matches!(self.map[p], MapValue::StairsUp | MapValue::StairsDown)
+            {
+                map_list[p.1] = &self.value_map.solve;
+            }
+        }
+        // move
+        if let Some(move_list) = &self.move_list {
+            for p in move_list.iter().filter(|p| p.0 == self.floor) {
+                map_list[p.1] = &self.value_map.r#move;
+            }
+        }
+        // player
+        map_list[self.player.pos] = &self.value_map.player;
+
+        lines.extend(
+            map_list
+                .iter()
+                .map(|line| line.iter().map(|s| s.as_str()).collect::<String>()),
+        );
+        println!("{}", lines.join("\n"));
+        Ok(())
+    }
+
+    fn inner_move(&mut self, status: MoveStatus) -> RunOnceResult {
+        match self.move_to(status) {
+            Ok(move_list) => {
+                self.move_list = Some(move_list);
+                RunOnceResult::Ok
+            }
+            Err(err) => match err {
+                MazeError::CanNotMove => RunOnceResult::CanNotMove,
+                other => RunOnceResult::Error(other.to_string()),
+            },
+        }
+    }
+
+    fn run_once(&mut self, cmd: &str) -> RunOnceResult {
+        let itr = match shlex::split(cmd) {
+            Some(itr) => itr,
+            None => return RunOnceResult::InValid,
+        };
+        let cli = match Cli::try_parse_from(itr) {
+            Ok(cli) => cli,
+            Err(e) => return RunOnceResult::CmdError(e.to_string()),
+        };
+        match cli {
+            Cli::Up => return self.inner_move(MoveStatus::Up),
+            Cli::Down => return self.inner_move(MoveStatus::Down),
+            Cli::Left => return self.inner_move(MoveStatus::Left),
+            Cli::Right => return self.inner_move(MoveStatus::Right),
+            Cli::Upstairs => return self.inner_move(MoveStatus::Upstairs),
+            Cli::Downstairs => return self.inner_move(MoveStatus::Downstairs),
+            Cli::Restart => self.restart(),
+            Cli::New(sub) => {
+                self.map.options.maze = match sub.options(&self.map.options.maze) {
+                    Ok(options) => options,
+                    Err(e) => return RunOnceResult::Error(e.to_string()),
+                };
+                if let Some(state) = sub.seed {
+                    self.map.random = R::seed_from_u64(state);
+                }
+                let floors = self.map.floor_count();
+                if let Err(e) = self.map.generate(floors, sub.row, sub.column) {
+                    return RunOnceResult::Error(e.to_string());
+                }
+                self.player = Player::new(self.map.st.1, "player");
+                self.restart();
+            }
+            Cli::Solve(sub) => {
+                if sub.algo.is_some() || sub.explored {
+                    return RunOnceResult::CmdError(String::from("only bfs is available here"));
+                }
+                self.will_solve = true;
+            }
+            Cli::UnSolve => self.will_solve = false,
+            Cli::Quit => return RunOnceResult::Quit,
+            Cli::Display => return RunOnceResult::Display,
+            Cli::Descend(_)
+            | Cli::Hint(_)
+            | Cli::Tower(_)
+            | Cli::Grid(_)
+            | Cli::Export(_)
+            | Cli::Stats
+            | Cli::Code
+            | Cli::AnimateGenerate(_) => {
+                return RunOnceResult::CmdError(String::from("not available in tower mode"))
+            }
+        };
+        RunOnceResult::Ok
+    }
+
+    pub fn run(&mut self) -> MazeResult<()> {
+        println!("game start!");
+        self.display().unwrap();
+        loop {
+            let mut buf = String::new();
+            std::io::stdin().read_line(&mut buf).unwrap_or_else(|e| {
+                println!("{}", e);
+                0
+            });
+            self.move_list = None;
+            match self.run_once(buf.trim()) {
+                RunOnceResult::Ok | RunOnceResult::Display => {
+                    self.display().unwrap_or_else(|e| println!("{}", e))
+                }
+                RunOnceResult::InValid => println!("invalid input"),
+                RunOnceResult::CanNotMove => println!("can not move"),
+                RunOnceResult::Quit => break,
+                RunOnceResult::Error(err) => println!("error: {}", err),
+                RunOnceResult::CmdError(err) => println!("{}", err),
+                RunOnceResult::Message(msg) => println!("{}", msg),
+                RunOnceResult::Descend => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_tower() {
+        let random = ChaCha8Rng::seed_from_u64(2);
+        let mut game =
+            ConsoleTower::new_with_options(3, 9, 13, random, Default::default()).unwrap();
+        let path = game.map().solve(game.pos()).unwrap();
+        // 沿着解的路线一步一步走到终点
+        for w in path.windows(2) {
+            if game.pos() != w[0] {
+                continue;
+            }
+            let status = if w[1].0 > w[0].0 {
+                MoveStatus::Upstairs
+            } else if w[1].0 < w[0].0 {
+                MoveStatus::Downstairs
+            } else {
+                [
+                    MoveStatus::Up,
+                    MoveStatus::Down,
+                    MoveStatus::Left,
+                    MoveStatus::Right,
+                ]
+                .into_iter()
+                .find(|s| s.get_next(w[0].1) == w[1].1)
+                .unwrap()
+            };
+            game.move_to(status).unwrap();
+            assert!(path.contains(&game.pos()));
+        }
+        assert!(game.is_win());
+        assert!(matches!(game.run_once("up"), RunOnceResult::Error(_)));
+    }
+}
@@ -0,0 +1,188 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    generator::{parse_key_values, parse_value},
+    maze_map::MazeMap,
+    maze_options::MazeOptions,
+    random::derive_seed,
+    stats::MazeStats,
+};
+
+// 闭区间，两端都可以省略，写作 `a..b`、`a..`、`..b`，只写一个数表示下限
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Limit<T> {
+    pub min: Option<T>,
+    pub max: Option<T>,
+}
+
+impl<T> Limit<T>
+where
+    T: PartialOrd + Copy,
+{
+    pub fn new(min: Option<T>, max: Option<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl<T> Display for Limit<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(min) = &self.min {
+            write!(f, "{}", min)?;
+        }
+        write!(f, "..")?;
+        if let Some(max) = &self.max {
+            write!(f, "{}", max)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromStr for Limit<T>
+where
+    T: FromStr,
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bound = |v: &str| -> Result<Option<T>, String> {
+            if v.is_empty() {
+                return Ok(None);
+            }
+            v.parse()
+                .map(Some)
+                .map_err(|_| format!("invalid limit: {}", s))
+        };
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => (bound(min)?, bound(max)?),
+            None => (bound(s)?, None),
+        };
+        Ok(Self { min, max })
+    }
+}
+
+// 生成迷宫时要满足的难度条件，没有设置的条件不做限制
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Difficulty {
+    pub solution: Limit<usize>,
+    // 死路占全部位置的比例
+    pub dead_ends: Limit<f64>,
+    pub decisions: Limit<usize>,
+    pub turns: Limit<usize>,
+    pub tortuosity: Limit<f64>,
+}
+
+impl Difficulty {
+    pub fn check(&self, stats: &MazeStats) -> bool {
+        self.solution.contains(stats.solution_length)
+            && self.dead_ends.contains(stats.dead_end_ratio())
+            && self.decisions.contains(stats.decisions)
+            && self.turns.contains(stats.turns)
+            && self.tortuosity.contains(stats.tortuosity)
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "solution={},dead-ends={},decisions={},turns={},tortuosity={}",
+            self.solution, self.dead_ends, self.decisions, self.turns, self.tortuosity
+        )
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut difficulty = Self::default();
+        for (key, value) in parse_key_values(s)? {
+            match key {
+                "solution" => difficulty.solution = parse_value(key, value)?,
+                "dead-ends" => difficulty.dead_ends = parse_value(key, value)?,
+                "decisions" => difficulty.decisions = parse_value(key, value)?,
+                "turns" => difficulty.turns = parse_value(key, value)?,
+                "tortuosity" => difficulty.tortuosity = parse_value(key, value)?,
+                _ => return Err(format!("unknown difficulty option: {}", key)),
+            }
+        }
+        Ok(difficulty)
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng + SeedableRng,
+{
+    // 依次用主种子派生出的种子生成，直到满足难度条件
+    // 返回迷宫和它的种子，用 `new_with_seed` 和相同的参数可以重新生成
+    pub fn new_with_difficulty(
+        row: usize,
+        column: usize,
+        seed: u64,
+        options: MazeOptions,
+        difficulty: &Difficulty,
+        attempts: usize,
+    ) -> MazeResult<(Self, u64)> {
+        for i in 0..attempts {
+            let seed = derive_seed(seed, i as u64);
+            let maze = Self::new_with_seed(row, column, seed, options.clone())?;
+            if difficulty.check(&maze.stats()?) {
+                return Ok((maze, seed));
+            }
+        }
+        Err(MazeError::Exhausted(attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use crate::generator::Generator;
+
+    use super::*;
+
+    #[test]
+    fn test_difficulty() {
+        let difficulty: Difficulty = "solution=60..,dead-ends=..0.2,decisions=8".parse().unwrap();
+        let options = MazeOptions::new(Generator::Kruskal);
+        let (maze, seed) =
+            MazeMap::<StdRng>::new_with_difficulty(15, 21, 7, options.clone(), &difficulty, 1000)
+                .unwrap();
+        let stats = maze.stats().unwrap();
+        assert!(stats.solution_length >= 60 && stats.decisions >= 8);
+        assert!(stats.dead_end_ratio() <= 0.2);
+        // 种子可以重现迷宫，主种子相同时结果也相同
+        assert_eq!(maze.seed, Some(seed));
+        let again = MazeMap::<StdRng>::new_with_seed(15, 21, seed, options.clone()).unwrap();
+        assert_eq!(again.map(), maze.map());
+        let same =
+            MazeMap::<StdRng>::new_with_difficulty(15, 21, 7, options.clone(), &difficulty, 1000);
+        assert_eq!(same.unwrap().1, seed);
+
+        let impossible: Difficulty = "solution=..3".parse().unwrap();
+        let res = MazeMap::<StdRng>::new_with_difficulty(15, 21, 7, options, &impossible, 20);
+        assert_eq!(res.unwrap_err(), MazeError::Exhausted(20));
+    }
+
+    #[test]
+    fn test_parse_difficulty() {
+        let difficulty: Difficulty = "solution=10..20,tortuosity=1.5".parse().unwrap();
+        assert_eq!(difficulty.solution, Limit::new(Some(10), Some(20)));
+        assert_eq!(difficulty.tortuosity, Limit::new(Some(1.5), None));
+        assert_eq!(difficulty.to_string().parse(), Ok(difficulty));
+        assert!("solution=a..b".parse::<Difficulty>().is_err());
+        assert!("walls=3".parse::<Difficulty>().is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::Point,
+    solver::{crossing_axis, moves, ROOT},
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Node {
+    distance: usize,
+    // 起点没有父节点
+    parent: Option<Point>,
+}
+
+// 从一个或多个起点出发 BFS 得到的到每个位置的步数和父节点组成的树
+// 有多个起点时是到最近一个起点的距离
+// 交叉点横竖各经过一次，两次分别保存，按位置查询时取较近的一次
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DistanceMap {
+    sources: Vec<Point>,
+    cells: Grid<Option<Node>>,
+    // 经过交叉点的方向是否为竖直
+    crossings: HashMap<(Point, bool), Node>,
+}
+
+impl DistanceMap {
+    fn build<Random>(maze: &MazeMap<Random>, sources: &[Point]) -> MazeResult<Self>
+    where
+        Random: Rng,
+    {
+        if sources.is_empty() {
+            return Err(MazeError::SolveException);
+        }
+        let mut res = Self {
+            sources: sources.to_vec(),
+            cells: Grid::new(maze.row(), maze.column(), None),
+            crossings: HashMap::new(),
+        };
+        let mut queue = VecDeque::new();
+        let root = Node {
+            distance: 0,
+            parent: None,
+        };
+        for &p in sources {
+            if maze.is_overrange(p) || !maze[p].is_passable() {
+                return Err(MazeError::SolveException);
+            }
+            if res.cells[p].is_some() {
+                continue;
+            }
+            res.cells[p] = Some(root);
+            if maze[p] == MapValue::Crossing {
+                res.crossings.insert((p, true), root);
+                res.crossings.insert((p, false), root);
+            }
+            queue.push_back((p, ROOT));
+        }
+        while let Some((p, dir)) = queue.pop_front() {
+            let distance = res.node(p, crossing_axis(maze, p, dir)).unwrap().distance + 1;
+            for (d, q) in moves(maze, p, dir) {
+                let axis = crossing_axis(maze, q, d);
+                if res.node(q, axis).is_some() {
+                    continue;
+                }
+                let node = Node {
+                    distance,
+                    parent: Some(p),
+                };
+                if let Some(axis) = axis {
+                    res.crossings.insert((q, axis), node);
+                }
+                // 交叉点第一次到达时的距离较近
+                if res.cells[q].is_none() {
+                    res.cells[q] = Some(node);
+                }
+                queue.push_back((q, d));
+            }
+        }
+        Ok(res)
+    }
+
+    fn node(&self, p: Point, axis: Option<bool>) -> Option<&Node> {
+        match axis {
+            Some(axis) => self.crossings.get(&(p, axis)),
+            None => self.cells[p].as_ref(),
+        }
+    }
+
+    pub fn sources(&self) -> &[Point] {
+        &self.sources
+    }
+
+    pub fn row(&self) -> usize {
+        self.cells.row()
+    }
+
+    pub fn column(&self) -> usize {
+        self.cells.column()
+    }
+
+    // 到最近的起点的步数，不能到达时为 None
+    pub fn distance(&self, p: Point) -> Option<usize> {
+        if self.cells.is_overrange(p) {
+            return None;
+        }
+        self.cells[p].map(|node| node.distance)
+    }
+
+    pub fn parent(&self, p: Point) -> Option<Point> {
+        if self.cells.is_overrange(p) {
+            return None;
+        }
+        self.cells[p]?.parent
+    }
+
+    pub fn is_reachable(&self, p: Point) -> bool {
+        self.distance(p).is_some()
+    }
+
+    // 从最近的起点到 p 的最短路径，沿父节点走回起点，长度为距离加一
+    pub fn path_to(&self, p: Point) -> Option<Vec<Point>> {
+        let mut node = *self.cells.get(p)?.as_ref()?;
+        let mut res = vec![p];
+        let mut child = p;
+        while let Some(parent) = node.parent {
+            // 从交叉点走到子节点的方向就是穿过交叉点的方向，竖直经过时列不变
+            node = match self.crossings.get(&(parent, parent.1 == child.1)) {
+                Some(&node) => node,
+                None => self.cells[parent]?,
+            };
+            res.push(parent);
+            child = parent;
+        }
+        res.reverse();
+        Some(res)
+    }
+
+    // 能到达的位置和距离，按行优先的顺序
+    pub fn reachable(&self) -> impl Iterator<Item = (Point, usize)> + '_ {
+        self.cells
+            .points()
+            .zip(self.cells.iter())
+            .filter_map(|(p, node)| Some((p, node.as_ref()?.distance)))
+    }
+
+    // 距离恰好为 distance 的位置
+    pub fn at_distance(&self, distance: usize) -> Vec<Point> {
+        self.reachable()
+            .filter(|&(_, d)| d == distance)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    // 最远的位置，距离相同时取行优先顺序中靠前的
+    pub fn farthest(&self) -> Option<(Point, usize)> {
+        self.reachable()
+            .fold(None, |best: Option<(Point, usize)>, (p, d)| match best {
+                Some((_, b)) if b >= d => best,
+                _ => Some((p, d)),
+            })
+    }
+
+    // 可以通过但不能从起点到达的位置
+    pub fn unreachable<Random>(&self, maze: &MazeMap<Random>) -> Vec<Point>
+    where
+        Random: Rng,
+    {
+        maze.map()
+            .points()
+            .filter(|&p| maze[p].is_passable() && !self.is_reachable(p))
+            .collect()
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    pub fn distance_map(&self, from: Point) -> MazeResult<DistanceMap> {
+        DistanceMap::build(self, &[from])
+    }
+
+    // 多个起点同时出发，每个位置记录到最近的起点的距离
+    pub fn distance_map_multi(&self, sources: &[Point]) -> MazeResult<DistanceMap> {
+        DistanceMap::build(self, sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{maze_options::MazeOptions, wrap::Wrap};
+
+    use super::*;
+
+    #[test]
+    fn test_distance_map() {
+        for (generator, loops, wrap) in [
+            ("kruskal", 30, Wrap::Torus),
+            ("weave:density=80", 20, Wrap::None),
+            ("dungeon", 0, Wrap::None),
+        ] {
+            let options = MazeOptions {
+                generator: generator.parse().unwrap(),
+                loops,
+                wrap,
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(4);
+            let maze = MazeMap::new_with_options(22, 30, random, options).unwrap();
+            let distances = maze.distance_map(maze.ed).unwrap();
+            // 与求解得到的最短路径长度一致，沿父节点走回的路径也是最短路径
+            for p in [maze.st, Point(0, 0), Point(10, 10)] {
+                if !maze[p].is_passable() {
+                    continue;
+                }
+                let solution = maze.solve(p).unwrap();
+                assert_eq!(
+                    distances.distance(p),
+                    Some(solution.len() - 1),
+                    "{}",
+                    generator
+                );
+                let mut path = distances.path_to(p).unwrap();
+                path.reverse();
+                assert_eq!(path.len(), solution.len());
+                assert_eq!((path[0], path[path.len() - 1]), (p, maze.ed));
+                let mut dir = ROOT;
+                for w in path.windows(2) {
+                    dir = moves(&maze, w[0], dir).find(|&(_, q)| q == w[1]).unwrap().0;
+                }
+            }
+            assert!(distances.unreachable(&maze).is_empty());
+            let (far, d) = distances.farthest().unwrap();
+            assert!(distances.at_distance(d).contains(&far));
+            assert!(distances.at_distance(d + 1).is_empty());
+        }
+
+        // 多个起点时取最近的一个
+        let random = StdRng::seed_from_u64(5);
+        let maze = MazeMap::new_with_random(21, 31, random).unwrap();
+        let both = maze.distance_map_multi(&[maze.st, maze.ed]).unwrap();
+        let (from_st, from_ed) = (
+            maze.distance_map(maze.st).unwrap(),
+            maze.distance_map(maze.ed).unwrap(),
+        );
+        for (p, d) in both.reachable() {
+            let nearest = from_st
+                .distance(p)
+                .unwrap()
+                .min(from_ed.distance(p).unwrap());
+            assert_eq!(d, nearest);
+            let path = both.path_to(p).unwrap();
+            assert!(both.sources().contains(&path[0]));
+        }
+        assert!(maze.distance_map_multi(&[]).is_err());
+
+        // 被墙隔开的区域不能到达
+        let mut maze = maze;
+        let (st, ed) = (maze.st, maze.ed);
+        for p in maze.neighbours(st) {
+            maze.set(p, MapValue::Wall);
+        }
+        let distances = maze.distance_map(st).unwrap();
+        assert_eq!(distances.reachable().count(), 1);
+        assert!(distances.unreachable(&maze).contains(&ed));
+        assert_eq!(distances.path_to(ed), None);
+    }
+}
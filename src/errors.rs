@@ -7,6 +7,14 @@ pub enum MazeError {
     SolveException,
     GameWin,
     CanNotMove,
+    // 提示次数已经用完
+    NoHintsLeft,
+    // 尝试次数用完仍没有满足条件的迷宫
+    Exhausted(usize),
+    // 无法解析的迷宫代码
+    Code(String),
+    // 求最优顺序时途经点太多
+    TooManyWaypoints(usize),
 }
 
 impl Display for MazeError {
@@ -17,6 +25,12 @@ impl Display for MazeError {
             Self::SolveException => write!(f, "solve failed"),
             Self::GameWin => write!(f, "game is over"),
             Self::CanNotMove => write!(f, "can not move"),
+            Self::NoHintsLeft => write!(f, "no hints left"),
+            Self::Exhausted(attempts) => {
+                write!(f, "no maze matched the difficulty in {} attempts", attempts)
+            }
+            Self::Code(value) => write!(f, "invalid maze code: {}", value),
+            Self::TooManyWaypoints(count) => write!(f, "too many waypoints: {}", count),
         }
     }
 }
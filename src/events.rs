@@ -0,0 +1,208 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, grid::Grid, map_value::MapValue, maze_map::MazeMap, point::Point};
+
+type TMap = Grid<MapValue>;
+
+// 生成过程中发生的事件，按顺序重放就能得到最终的地图
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum GenerationEvent {
+    // 位置被打通，值为打通后的道路或交叉点
+    Carve(Point, MapValue),
+    // 位置被填回墙，或者成为遮罩外的位置
+    Fill(Point, MapValue),
+    // 考虑是否打通这个位置
+    Consider(Point),
+    // 回溯到这个位置继续生成
+    Backtrack(Point),
+    Start(Point),
+    End(Point),
+}
+
+impl GenerationEvent {
+    pub fn point(&self) -> Point {
+        match *self {
+            Self::Carve(p, _)
+            | Self::Fill(p, _)
+            | Self::Consider(p)
+            | Self::Backtrack(p)
+            | Self::Start(p)
+            | Self::End(p) => p,
+        }
+    }
+}
+
+// 生成开始前的地图和之后的全部事件
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct GenerationRecord {
+    pub initial: TMap,
+    pub events: Vec<GenerationEvent>,
+}
+
+impl GenerationRecord {
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {
+            events: self.events.iter(),
+            map: self.initial.clone(),
+        }
+    }
+}
+
+// 逐个应用事件，每次 `next` 之后 `map` 就是这个事件发生后的地图
+pub struct Replay<'a> {
+    events: std::slice::Iter<'a, GenerationEvent>,
+    pub map: TMap,
+}
+
+impl Iterator for Replay<'_> {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = *self.events.next()?;
+        match event {
+            GenerationEvent::Carve(p, value) | GenerationEvent::Fill(p, value) => {
+                self.map[p] = value
+            }
+            GenerationEvent::Start(p) => self.map[p] = MapValue::St,
+            GenerationEvent::End(p) => self.map[p] = MapValue::Ed,
+            GenerationEvent::Consider(_) | GenerationEvent::Backtrack(_) => {}
+        }
+        Some(event)
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    pub fn is_recording(&self) -> bool {
+        self.record.is_some()
+    }
+
+    pub(crate) fn emit(&mut self, event: GenerationEvent) {
+        if let Some(record) = self.record.as_mut() {
+            record.events.push(event);
+        }
+    }
+
+    // 修改地图并记录事件，生成器都通过它修改地图
+    pub(crate) fn set(&mut self, p: Point, value: MapValue) {
+        if self[p] == value {
+            return;
+        }
+        self[p] = value;
+        if value.is_passable() {
+            self.emit(GenerationEvent::Carve(p, value));
+        } else {
+            self.emit(GenerationEvent::Fill(p, value));
+        }
+    }
+
+    // 生成器开始前调用，之前对地图的修改都算作初始状态
+    pub(crate) fn begin_record(&mut self) {
+        let map = self.map().clone();
+        if let Some(record) = self.record.as_mut() {
+            record.initial = map;
+            record.events.clear();
+        }
+    }
+
+    // 加边框后地图变大，已有的事件跟着移动，边框上的开口作为新的事件
+    pub(crate) fn record_frame(&mut self, framed: &TMap) {
+        let Some(mut record) = self.record.take() else {
+            return;
+        };
+        let mut initial = Grid::new(framed.row(), framed.column(), MapValue::Border);
+        let mut before = initial.clone();
+        for (i, line) in record.initial.rows().enumerate() {
+            for (j, &value) in line.iter().enumerate() {
+                initial[i + 1][j + 1] = value;
+                before[i + 1][j + 1] = self.map()[i][j];
+            }
+        }
+        record.initial = initial;
+        for event in record.events.iter_mut() {
+            *event = match *event {
+                GenerationEvent::Carve(p, value) => GenerationEvent::Carve(p + (1, 1), value),
+                GenerationEvent::Fill(p, value) => GenerationEvent::Fill(p + (1, 1), value),
+                GenerationEvent::Consider(p) => GenerationEvent::Consider(p + (1, 1)),
+                GenerationEvent::Backtrack(p) => GenerationEvent::Backtrack(p + (1, 1)),
+                GenerationEvent::Start(p) => GenerationEvent::Start(p + (1, 1)),
+                GenerationEvent::End(p) => GenerationEvent::End(p + (1, 1)),
+            };
+        }
+        for (p, &value) in framed.points().zip(framed.iter()) {
+            if before[p] == value || matches!(value, MapValue::St | MapValue::Ed) {
+                continue;
+            }
+            record.events.push(if value.is_passable() {
+                GenerationEvent::Carve(p, value)
+            } else {
+                GenerationEvent::Fill(p, value)
+            });
+        }
+        self.record = Some(record);
+    }
+
+    // 生成新的迷宫，同时返回生成过程
+    pub fn generate_with_events(
+        &mut self,
+        row: usize,
+        column: usize,
+    ) -> MazeResult<GenerationRecord> {
+        self.record = Some(GenerationRecord::default());
+        let res = self.generate(row, column);
+        let record = self.record.take().unwrap_or_default();
+        res.map(|_| record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_generation_events() {
+        let generators = [
+            Generator::RecursiveBacktracker,
+            Generator::Prim,
+            Generator::Kruskal,
+            Generator::Eller,
+            "growing-tree:newest".parse().unwrap(),
+            "division".parse().unwrap(),
+            "dungeon".parse().unwrap(),
+            "weave".parse().unwrap(),
+        ];
+        for generator in generators {
+            for (loops, frame) in [(0, false), (50, true)] {
+                let options = MazeOptions {
+                    generator,
+                    loops,
+                    frame,
+                    ..Default::default()
+                };
+                let random = StdRng::seed_from_u64(1);
+                let mut maze = MazeMap::new_with_options(5, 5, random, options).unwrap();
+                let record = maze.generate_with_events(15, 21).unwrap();
+                assert!(!maze.is_recording());
+                // 重放全部事件后与生成的地图一致
+                let mut replay = record.replay();
+                let events: Vec<GenerationEvent> = replay.by_ref().collect();
+                assert_eq!(&replay.map, maze.map(), "{}", generator);
+                assert!(events.contains(&GenerationEvent::Start(maze.st)));
+                assert_eq!(events.last(), Some(&GenerationEvent::End(maze.ed)));
+
+                // 记录事件不影响生成结果
+                let random = StdRng::seed_from_u64(1);
+                let options = maze.options.clone();
+                let mut again = MazeMap::new_with_options(5, 5, random, options).unwrap();
+                again.generate(15, 21).unwrap();
+                assert_eq!(again.map(), maze.map());
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    events::GenerationEvent,
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    maze_options::PIPELINE_VERSION,
+    point::{Point, OFFSETS},
+    wrap::Wrap,
+};
+
+type TMap = Grid<MapValue>;
+
+// 在地图外围加一圈 `MapValue::Border`，把起点和终点移到边框上的开口
+// 起点和终点不在边缘时，沿最近的方向穿过墙打通到边框
+pub fn add_frame(map: &TMap, st: Point, ed: Point) -> MazeResult<(TMap, Point, Point)> {
+    add_frame_with(map, st, ed, PIPELINE_VERSION)
+}
+
+// 按生成时的版本打通，第 1 版不检查打通的位置两侧
+pub(crate) fn add_frame_with(
+    map: &TMap,
+    st: Point,
+    ed: Point,
+    pipeline: u8,
+) -> MazeResult<(TMap, Point, Point)> {
+    let (row, column) = (map.row(), map.column());
+    let mut res = Grid::new(row + 2, column + 2, MapValue::Border);
+    for (i, line) in map.rows().enumerate() {
+        for (j, &value) in line.iter().enumerate() {
+            res[i + 1][j + 1] = value;
+        }
+    }
+    let openings = match pipeline {
+        1 => [st, ed].map(|p| opening_line(map, p, pipeline)),
+        _ => opening_pair(map, st, ed, pipeline).map_or([None, None], |(a, b)| [Some(a), Some(b)]),
+    };
+    let mut gates = vec![];
+    for (p, opening) in [st, ed].into_iter().zip(openings) {
+        let (line, gate) = match opening {
+            Some(res) => res,
+            None => return Err(MazeError::Init(format!("{} 无法打通到边框", p))),
+        };
+        for q in line {
+            res[q + (1, 1)] = MapValue::Road;
+        }
+        res[p + (1, 1)] = MapValue::Road;
+        gates.push(gate + (1, 1));
+    }
+    if gates[0] == gates[1] {
+        return Err(MazeError::Init(String::from("入口和出口重合")));
+    }
+    res[gates[0]] = MapValue::St;
+    res[gates[1]] = MapValue::Ed;
+    Ok((res, gates[0], gates[1]))
+}
+
+// 去掉边框，开口内侧的位置作为起点和终点
+pub fn remove_frame(map: &TMap, st: Point, ed: Point) -> (TMap, Point, Point) {
+    let (row, column) = (map.row() - 2, map.column() - 2);
+    let mut res = Grid::new(row, column, MapValue::Wall);
+    for (i, line) in res.rows_mut().enumerate() {
+        line.copy_from_slice(&map[i + 1][1..=column]);
+    }
+    let inner = |p: Point| {
+        let i = p.0.clamp(1, row as i32) - 1;
+        let j = p.1.clamp(1, column as i32) - 1;
+        Point(i, j)
+    };
+    let (st, ed) = (inner(st), inner(ed));
+    res[st] = MapValue::St;
+    res[ed] = MapValue::Ed;
+    (res, st, ed)
+}
+
+type Opening = (Vec<Point>, Point);
+
+// 从 p 沿直线走出地图，中间只经过墙的全部走法，按长度排序，返回要打通的位置和地图外的开口
+// 打通的位置两侧也必须是墙，否则会连到别的通道，或者穿过遮罩外的位置
+fn opening_lines(map: &TMap, p: Point, pipeline: u8) -> Vec<Opening> {
+    let mut res = vec![];
+    for offset in OFFSETS {
+        let mut line = vec![];
+        let mut q = p + offset;
+        while !map.is_overrange(q) && !map[q].is_passable() {
+            line.push(q);
+            q = q + offset;
+        }
+        if !map.is_overrange(q) {
+            continue;
+        }
+        let side = Point(offset.1, offset.0);
+        let is_wall = |q: Point| map.get(q).is_none_or(|&v| v == MapValue::Wall);
+        if pipeline >= 2
+            && !line
+                .iter()
+                .all(|&q| map[q] == MapValue::Wall && is_wall(q + side) && is_wall(q - side))
+        {
+            continue;
+        }
+        res.push((line, q));
+    }
+    res.sort_by_key(|(line, _)| line.len());
+    res
+}
+
+pub(crate) fn opening_line(map: &TMap, p: Point, pipeline: u8) -> Option<Opening> {
+    opening_lines(map, p, pipeline).into_iter().next()
+}
+
+// 起点和终点各自的开口，两条通道和开口互不相邻，否则起点和终点之间多出一条路
+fn opening_pair(map: &TMap, st: Point, ed: Point, pipeline: u8) -> Option<(Opening, Opening)> {
+    let cells = |(line, gate): &Opening| line.iter().copied().chain([*gate]).collect::<Vec<_>>();
+    let touches = |a: &Opening, b: &Opening| {
+        let b = cells(b);
+        cells(a).into_iter().any(|p| {
+            b.iter()
+                .any(|&q| (p.0 - q.0).abs() + (p.1 - q.1).abs() <= 1)
+        })
+    };
+    let eds = opening_lines(map, ed, pipeline);
+    opening_lines(map, st, pipeline)
+        .into_iter()
+        .flat_map(|a| eds.iter().map(move |b| (a.clone(), b.clone())))
+        .filter(|(a, b)| !touches(a, b))
+        .min_by_key(|(a, b)| a.0.len() + b.0.len())
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    pub fn is_framed(&self) -> bool {
+        self.options.frame
+    }
+
+    // 加边框时起点和终点只能放在能打通到边框的道路上
+    pub(crate) fn is_placeable(&self, p: Point) -> bool {
+        !self.options.frame || opening_line(self.map(), p, self.options.pipeline).is_some()
+    }
+
+    // 起点和终点打通到边框的通道不能相邻
+    pub(crate) fn is_placeable_pair(&self, st: Point, ed: Point) -> bool {
+        !self.options.frame
+            || self.options.pipeline < 2
+            || opening_pair(self.map(), st, ed, self.options.pipeline).is_some()
+    }
+
+    // 加边框时在内部缩小一圈生成，保证地图总大小不变
+    pub(crate) fn init_frame(&mut self) -> MazeResult<()> {
+        if !self.options.frame {
+            return Ok(());
+        }
+        if self.options.wrap != Wrap::None {
+            return Err(MazeError::Init(String::from("环绕的迷宫不能加边框")));
+        }
+        if self.row() < 4 || self.column() < 4 {
+            return Err(MazeError::Init(String::from("加边框时行和列不能小于4")));
+        }
+        self.set_map(Grid::new(self.row() - 2, self.column() - 2, MapValue::Wall));
+        self.inst_st = Default::default();
+        Ok(())
+    }
+
+    pub(crate) fn finish_frame(&mut self) -> MazeResult<()> {
+        if !self.options.frame {
+            return Ok(());
+        }
+        let (map, st, ed) = add_frame_with(self.map(), self.st, self.ed, self.options.pipeline)?;
+        self.record_frame(&map);
+        self.set_map(map);
+        self.st = st;
+        self.ed = ed;
+        self.emit(GenerationEvent::Start(st));
+        self.emit(GenerationEvent::End(ed));
+        // 房间的坐标也要跟着边框移动
+        for room in self.rooms.iter_mut() {
+            room.top += 1;
+            room.left += 1;
+        }
+        Ok(())
+    }
+
+    // 导出地图，with_frame 决定是否带边框，与生成时是否加了边框无关
+    pub fn export(&self, with_frame: bool) -> MazeResult<TMap> {
+        match (self.is_framed(), with_frame) {
+            (true, false) => Ok(remove_frame(self.map(), self.st, self.ed).0),
+            (false, true) => Ok(add_frame(self.map(), self.st, self.ed)?.0),
+            _ => Ok(self.map().clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{
+        generator::Generator, mask::Mask, maze_options::MazeOptions, placement::Placement,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_frame() {
+        for placement in [Placement::Corner, Placement::Farthest, Placement::Border] {
+            for seed in 0..5 {
+                let options = MazeOptions {
+                    generator: Generator::Prim,
+                    placement,
+                    frame: true,
+                    ..Default::default()
+                };
+                let random = StdRng::seed_from_u64(seed);
+                let maze = MazeMap::new_with_options(12, 20, random, options).unwrap();
+                assert_eq!((maze.row(), maze.column()), (12, 20));
+                // 边框上只有入口和出口两个开口
+                let ring: Vec<Point> = (0..12)
+                    .flat_map(|i| (0..20).map(move |j| Point(i, j)))
+                    .filter(|p| p.0 == 0 || p.0 == 11 || p.1 == 0 || p.1 == 19)
+                    .collect();
+                let gates: Vec<Point> = ring
+                    .iter()
+                    .copied()
+                    .filter(|&p| maze[p] != MapValue::Border)
+                    .collect();
+                assert_eq!(gates, {
+                    let mut v = vec![maze.st, maze.ed];
+                    v.sort_by_key(|p| (p.0, p.1));
+                    v
+                });
+                let path = maze.solve(maze.st).unwrap();
+                assert_eq!(*path.last().unwrap(), maze.ed);
+
+                let inner = maze.export(false).unwrap();
+                assert_eq!((inner.row(), inner.column()), (10, 18));
+                let count = |map: &TMap, value| map.iter().filter(|&&v| v == value).count();
+                assert_eq!(
+                    (count(&inner, MapValue::St), count(&inner, MapValue::Ed)),
+                    (1, 1)
+                );
+                assert_eq!(count(&inner, MapValue::Border), 0);
+                assert_eq!(&maze.export(true).unwrap(), maze.map());
+            }
+        }
+
+        let random = StdRng::seed_from_u64(1);
+        let maze = MazeMap::new_with_generator(11, 21, random, Generator::Kruskal).unwrap();
+        let framed = maze.export(true).unwrap();
+        assert_eq!((framed.row(), framed.column()), (13, 23));
+        assert_eq!(framed[0][1], MapValue::St);
+        assert_eq!(framed[1][1], MapValue::Road);
+
+        // 打通到边框时不能连到别的通道，完美迷宫加边框后仍然没有回路
+        for generator in [Generator::default(), Generator::Kruskal, Generator::Prim] {
+            for placement in [Placement::Corner, Placement::Random, Placement::Farthest] {
+                for seed in 0..10 {
+                    let options = MazeOptions {
+                        generator,
+                        placement,
+                        frame: true,
+                        ..Default::default()
+                    };
+                    let random = StdRng::seed_from_u64(seed);
+                    let maze = MazeMap::new_with_options(13, 20, random, options).unwrap();
+                    let roads = maze.map().iter().filter(|v| v.is_passable()).count();
+                    let links = maze
+                        .map()
+                        .points()
+                        .filter(|&p| maze[p].is_passable())
+                        .flat_map(|p| [p + (0, 1), p + (1, 0)])
+                        .filter(|&q| !maze.is_overrange(q) && maze[q].is_passable())
+                        .count();
+                    assert_eq!(links + 1, roads, "{:?} {:?} {}", generator, placement, seed);
+                }
+            }
+        }
+
+        // 不能穿过遮罩外的位置打通
+        let mask = Mask::from_ascii("..####\n.#####\n######\n#####.\n####..").unwrap();
+        for seed in 0..10 {
+            let options = MazeOptions {
+                generator: Generator::Kruskal,
+                placement: Placement::Random,
+                mask: Some(mask.clone()),
+                frame: true,
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(seed);
+            let maze = MazeMap::new_with_options(17, 22, random, options).unwrap();
+            let scaled = mask.resize(15, 20);
+            for p in maze.map().points() {
+                let inner = p - (1, 1);
+                let in_frame = (0..15).contains(&inner.0) && (0..20).contains(&inner.1);
+                if in_frame && !scaled.contains(inner) {
+                    assert!(!maze[p].is_passable(), "{} {}", seed, p);
+                }
+            }
+        }
+    }
+}
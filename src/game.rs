@@ -15,9 +15,16 @@ pub struct GameValueMap<T> {
     pub empty: T,
     pub r#move: T,
     pub solve: T,
+    pub explored: T,
     pub wall: T,
     pub road: T,
+    pub grass: T,
+    pub mud: T,
+    pub water: T,
+    pub crossing: T,
     pub border: T,
+    pub stairs_up: T,
+    pub stairs_down: T,
     pub player: T,
     pub st: T,
     pub ed: T,
@@ -33,7 +40,13 @@ impl ToGameValue for MapValue {
             MapValue::Empty => &map.empty,
             MapValue::Wall => &map.wall,
             MapValue::Road => &map.road,
+            MapValue::Grass => &map.grass,
+            MapValue::Mud => &map.mud,
+            MapValue::Water => &map.water,
+            MapValue::Crossing => &map.crossing,
             MapValue::Border => &map.border,
+            MapValue::StairsUp => &map.stairs_up,
+            MapValue::StairsDown => &map.stairs_down,
             MapValue::St => &map.st,
             MapValue::Ed => &map.ed,
         }
@@ -46,6 +59,7 @@ impl ToGameValue for GameValue {
             GameValue::Empty => &map.empty,
             GameValue::Move => &map.r#move,
             GameValue::Solve => &map.solve,
+            GameValue::Explored => &map.explored,
         }
     }
 }
@@ -73,18 +87,23 @@ where
     Random: Rng,
 {
     fn move_find_road(&mut self, p: Point, lp: Point) -> MazeResult<Option<Point>> {
-        let mut res = None;
-        for p2 in p.get_range_vec() {
-            if self.map().is_overrange(p2) {
-                continue;
+        // 交叉点不是路口，沿原方向穿过
+        if self.map()[p] == MapValue::Crossing {
+            let next = p + (p - lp);
+            if self.map().is_overrange(next) || !self.map()[next].is_passable() {
+                return Ok(None);
             }
+            return Ok(Some(next));
+        }
+        let mut res = None;
+        for p2 in self.map().neighbours(p) {
             if p2 == lp {
                 continue;
             }
-            if self.map()[p2] == MapValue::Wall {
+            if !self.map()[p2].is_passable() {
                 continue;
             }
-            if let Some(_) = res {
+            if res.is_some() {
                 return Ok(None);
             }
             res = Some(p2)
@@ -96,31 +115,30 @@ where
         if self.is_win()? {
             return Err(MazeError::GameWin);
         }
-        let mut lp = self.player().pos;
-        let mut p = r#move.get_next(lp);
-
-        if self.map().is_overrange(p) {
+        if r#move.is_stairs() {
             return Err(MazeError::CanNotMove);
         }
-        if self.map()[p] == MapValue::Wall {
+        let mut lp = self.player().pos;
+        let mut p = match self.map().wrap(r#move.get_next(lp)) {
+            Some(p) => p,
+            None => return Err(MazeError::CanNotMove),
+        };
+        if !self.map()[p].is_passable() {
             return Err(MazeError::CanNotMove);
         }
         let mut move_list = Vec::new();
         move_list.push(lp);
         move_list.push(p);
-        let mut step: i32 = 1;
+        // 步数按地形代价计算
+        let mut step = self.map()[p].cost() as i32;
         let mut next_road_raw = self.move_find_road(p, lp)?;
-        loop {
-            let next_road = match next_road_raw {
-                Some(p) => p,
-                None => break,
-            };
+        while let Some(next_road) = next_road_raw {
             if p == self.map().ed {
                 break;
             }
-            step += 1;
             lp = p;
             p = next_road;
+            step += self.map()[p].cost() as i32;
             move_list.push(p);
             next_road_raw = self.move_find_road(p, lp)?;
         }
@@ -144,8 +162,7 @@ where
         if self.map().is_overrange(pos) {
             return Err(MazeError::CanNotMove);
         }
-        let value = self.map()[pos];
-        if value != MapValue::Road && value != MapValue::St && value != MapValue::Ed {
+        if !self.map()[pos].is_passable() {
             return Err(MazeError::CanNotMove);
         }
         self.after_move_player(pos)
@@ -155,6 +172,51 @@ where
         self.map().solve(pos)
     }
 
+    // 从 a 走一步到相邻位置 b 的方向，考虑环绕
+    fn move_between(&self, a: Point, b: Point) -> MazeResult<MoveStatus> {
+        [
+            MoveStatus::Up,
+            MoveStatus::Down,
+            MoveStatus::Left,
+            MoveStatus::Right,
+        ]
+        .into_iter()
+        .find(|status| self.map().wrap(status.get_next(a)) == Some(b))
+        .ok_or(MazeError::SolveException)
+    }
+
+    // 沿解走下一步的方向
+    fn hint(&self) -> MazeResult<MoveStatus> {
+        if self.is_win()? {
+            return Err(MazeError::GameWin);
+        }
+        let path = self.solve(self.player().pos)?;
+        self.move_between(path[0], path[1])
+    }
+
+    // 解上下一个需要选择方向的路口和在那里要走的方向，包括当前位置
+    // 前面没有路口时返回当前位置和下一步的方向
+    fn hint_junction(&self) -> MazeResult<(Point, MoveStatus)> {
+        if self.is_win()? {
+            return Err(MazeError::GameWin);
+        }
+        let path = self.solve(self.player().pos)?;
+        let map = self.map();
+        let is_junction = |p: Point| {
+            map[p] != MapValue::Crossing
+                && map
+                    .neighbours(p)
+                    .into_iter()
+                    .filter(|&p2| map[p2].is_passable())
+                    .count()
+                    >= 3
+        };
+        let i = (0..path.len() - 1)
+            .find(|&i| is_junction(path[i]))
+            .unwrap_or(0);
+        Ok((path[i], self.move_between(path[i], path[i + 1])?))
+    }
+
     fn is_win(&self) -> MazeResult<bool> {
         Ok(self.map().ed == self.player().pos)
     }
@@ -1,12 +1,8 @@
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub enum GameValue {
+    #[default]
     Empty,
     Move,
     Solve,
-}
-
-impl Default for GameValue {
-    fn default() -> Self {
-        Self::Empty
-    }
+    Explored,
 }
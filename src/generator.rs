@@ -0,0 +1,575 @@
+pub mod aldous_broder;
+pub mod binary_tree;
+pub mod dungeon;
+pub mod eller;
+pub mod growing_tree;
+pub mod hunt_and_kill;
+pub mod kruskal;
+pub mod prim;
+pub mod recursive_backtracker;
+pub mod recursive_division;
+pub mod sidewinder;
+pub mod weave;
+pub mod wilson;
+
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{errors::MazeResult, grid::Grid, map_value::MapValue, maze_map::MazeMap, point::Point};
+
+pub use self::{
+    aldous_broder::AldousBroder,
+    binary_tree::BinaryTree,
+    dungeon::{Dungeon, DungeonOptions},
+    eller::{Eller, EllerRows},
+    growing_tree::{GrowingTree, GrowingTreeStrategy},
+    hunt_and_kill::HuntAndKill,
+    kruskal::Kruskal,
+    prim::Prim,
+    recursive_backtracker::RecursiveBacktracker,
+    recursive_division::{DivisionOptions, RecursiveDivision},
+    sidewinder::Sidewinder,
+    weave::{Weave, WeaveOptions},
+    wilson::Wilson,
+};
+
+// 在全是 `MapValue::Wall` 的地图上开辟道路，`st` 和 `ed` 由 `MazeMap` 放置
+pub trait MazeGenerator<Random>
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()>;
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Generator {
+    #[default]
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    HuntAndKill,
+    BinaryTree,
+    Sidewinder,
+    GrowingTree(GrowingTreeStrategy),
+    Wilson,
+    AldousBroder,
+    Eller,
+    RecursiveDivision(DivisionOptions),
+    Dungeon(DungeonOptions),
+    Weave(WeaveOptions),
+}
+
+impl Generator {
+    // 生成结果的版本，同一个种子在同一个版本下总是生成相同的迷宫
+    // 改变算法消耗随机数的方式时提高版本，并保留旧版本的实现，让旧的迷宫代码继续生成相同的迷宫
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::RecursiveBacktracker
+            | Self::Prim
+            | Self::Kruskal
+            | Self::HuntAndKill
+            | Self::BinaryTree
+            | Self::Sidewinder
+            | Self::GrowingTree(_)
+            | Self::Wilson
+            | Self::AldousBroder
+            | Self::Eller
+            | Self::RecursiveDivision(_)
+            | Self::Dungeon(_)
+            | Self::Weave(_) => 1,
+        }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for Generator
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        match self {
+            Self::RecursiveBacktracker => RecursiveBacktracker.generate(maze),
+            Self::Prim => Prim.generate(maze),
+            Self::Kruskal => Kruskal.generate(maze),
+            Self::HuntAndKill => HuntAndKill.generate(maze),
+            Self::BinaryTree => BinaryTree.generate(maze),
+            Self::Sidewinder => Sidewinder.generate(maze),
+            Self::GrowingTree(strategy) => GrowingTree::new(*strategy).generate(maze),
+            Self::Wilson => Wilson.generate(maze),
+            Self::AldousBroder => AldousBroder.generate(maze),
+            Self::Eller => Eller.generate(maze),
+            Self::RecursiveDivision(options) => RecursiveDivision::new(*options).generate(maze),
+            Self::Dungeon(options) => Dungeon::new(*options).generate(maze),
+            Self::Weave(options) => Weave::new(*options).generate(maze),
+        }
+    }
+}
+
+impl Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RecursiveBacktracker => write!(f, "backtracker"),
+            Self::Prim => write!(f, "prim"),
+            Self::Kruskal => write!(f, "kruskal"),
+            Self::HuntAndKill => write!(f, "hunt-and-kill"),
+            Self::BinaryTree => write!(f, "binary-tree"),
+            Self::Sidewinder => write!(f, "sidewinder"),
+            Self::GrowingTree(strategy) => write!(f, "growing-tree:{}", strategy),
+            Self::Wilson => write!(f, "wilson"),
+            Self::AldousBroder => write!(f, "aldous-broder"),
+            Self::Eller => write!(f, "eller"),
+            Self::RecursiveDivision(options) => write!(f, "division:{}", options),
+            Self::Dungeon(options) => write!(f, "dungeon:{}", options),
+            Self::Weave(options) => write!(f, "weave:{}", options),
+        }
+    }
+}
+
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+        let generator = match name.to_ascii_lowercase().as_str() {
+            "backtracker" | "recursive-backtracker" | "dfs" => Self::RecursiveBacktracker,
+            "prim" => Self::Prim,
+            "kruskal" => Self::Kruskal,
+            "hunt-and-kill" => Self::HuntAndKill,
+            "binary-tree" => Self::BinaryTree,
+            "sidewinder" => Self::Sidewinder,
+            "wilson" => Self::Wilson,
+            "aldous-broder" => Self::AldousBroder,
+            "eller" => Self::Eller,
+            "division" | "recursive-division" => {
+                let options = match arg {
+                    Some(arg) => arg.parse()?,
+                    None => DivisionOptions::default(),
+                };
+                return Ok(Self::RecursiveDivision(options));
+            }
+            "dungeon" => {
+                let options = match arg {
+                    Some(arg) => arg.parse()?,
+                    None => DungeonOptions::default(),
+                };
+                return Ok(Self::Dungeon(options));
+            }
+            "weave" => {
+                let options = match arg {
+                    Some(arg) => arg.parse()?,
+                    None => WeaveOptions::default(),
+                };
+                return Ok(Self::Weave(options));
+            }
+            "growing-tree" => {
+                let strategy = match arg {
+                    Some(arg) => arg.parse()?,
+                    None => GrowingTreeStrategy::default(),
+                };
+                return Ok(Self::GrowingTree(strategy));
+            }
+            _ => return Err(format!("unknown generator: {}", name)),
+        };
+        match arg {
+            Some(arg) => Err(format!("generator {} takes no argument: {}", name, arg)),
+            None => Ok(generator),
+        }
+    }
+}
+
+// 解析形如 `key=value,key=value` 的生成器参数
+pub(crate) fn parse_key_values(s: &str) -> Result<Vec<(&str, &str)>, String> {
+    s.split(',')
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.split_once('=')
+                .ok_or_else(|| format!("invalid generator option: {}", item))
+        })
+        .collect()
+}
+
+pub(crate) fn parse_value<T>(key: &str, value: &str) -> Result<T, String>
+where
+    T: FromStr,
+{
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", key, value))
+}
+
+// 以下为格子型生成器共用的工具：偶数坐标为格子，格子之间的奇数坐标为墙
+// 遮罩外的格子和墙都视为越界
+
+pub(crate) fn lattice_cells<Random>(maze: &MazeMap<Random>) -> Vec<Point>
+where
+    Random: Rng,
+{
+    let column = maze.column();
+    (0..maze.row())
+        .step_by(2)
+        .flat_map(|i| {
+            (0..column)
+                .step_by(2)
+                .map(move |j| Point(i as i32, j as i32))
+        })
+        .filter(|&p| !maze.is_outside(p))
+        .collect()
+}
+
+// 按格子之间的连通性分组，每组按扫描顺序排列
+pub(crate) fn lattice_components<Random>(maze: &MazeMap<Random>) -> Vec<Vec<Point>>
+where
+    Random: Rng,
+{
+    let mut seen = Grid::new(maze.row(), maze.column(), false);
+    let mut res = vec![];
+    for cell in lattice_cells(maze) {
+        if seen[cell] {
+            continue;
+        }
+        seen[cell] = true;
+        let mut component = vec![cell];
+        let mut stack = vec![cell];
+        while let Some(p) = stack.pop() {
+            for next in lattice_neighbours(maze, p) {
+                if !seen[next] {
+                    seen[next] = true;
+                    component.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        component.sort_by_key(|p| (p.0, p.1));
+        res.push(component);
+    }
+    res
+}
+
+pub(crate) fn lattice_columns<Random>(maze: &MazeMap<Random>) -> usize
+where
+    Random: Rng,
+{
+    maze.column().div_ceil(2)
+}
+
+pub(crate) fn lattice_len<Random>(maze: &MazeMap<Random>) -> usize
+where
+    Random: Rng,
+{
+    maze.row().div_ceil(2) * lattice_columns(maze)
+}
+
+pub(crate) fn lattice_index<Random>(maze: &MazeMap<Random>, p: Point) -> usize
+where
+    Random: Rng,
+{
+    (p.0 as usize / 2) * lattice_columns(maze) + p.1 as usize / 2
+}
+
+pub(crate) fn lattice_neighbours<Random>(maze: &MazeMap<Random>, p: Point) -> Vec<Point>
+where
+    Random: Rng,
+{
+    p.get_range_vec()
+        .into_iter()
+        .filter_map(|p2| {
+            let offset = p2 - p;
+            let wall = maze
+                .step(p, offset)
+                .filter(|&wall| !maze.is_outside(wall))?;
+            maze.step(wall, offset)
+        })
+        .filter(|&cell| cell != p && !maze.is_outside(cell))
+        .collect()
+}
+
+// 每条格子之间的边只出现一次，环绕时包括跨过接缝的边
+pub(crate) fn lattice_edges<Random>(maze: &MazeMap<Random>) -> Vec<(Point, Point)>
+where
+    Random: Rng,
+{
+    let mut res = vec![];
+    for cell in lattice_cells(maze) {
+        for offset in [Point(1, 0), Point(0, 1)] {
+            let next = maze
+                .step(cell, offset)
+                .filter(|&wall| !maze.is_outside(wall))
+                .and_then(|wall| maze.step(wall, offset));
+            if let Some(next) = next {
+                if next != cell && !maze.is_outside(next) {
+                    res.push((cell, next));
+                }
+            }
+        }
+    }
+    res
+}
+
+// 两个相邻格子之间的墙，环绕时可能在接缝上
+pub(crate) fn lattice_wall<Random>(maze: &MazeMap<Random>, from: Point, to: Point) -> Point
+where
+    Random: Rng,
+{
+    from.get_range_vec()
+        .into_iter()
+        .find_map(|p| {
+            let offset = p - from;
+            let wall = maze.step(from, offset)?;
+            (maze.step(wall, offset) == Some(to)).then_some(wall)
+        })
+        .unwrap_or(Point((from.0 + to.0) / 2, (from.1 + to.1) / 2))
+}
+
+pub(crate) fn is_carved<Random>(maze: &MazeMap<Random>, p: Point) -> bool
+where
+    Random: Rng,
+{
+    maze[p] != MapValue::Wall
+}
+
+pub(crate) fn carve_passage<Random>(maze: &mut MazeMap<Random>, from: Point, to: Point)
+where
+    Random: Rng,
+{
+    let wall = if from == to {
+        from
+    } else {
+        lattice_wall(maze, from, to)
+    };
+    maze.set(from, MapValue::Road);
+    maze.set(wall, MapValue::Road);
+    maze.set(to, MapValue::Road);
+}
+
+pub(crate) fn random_cell<Random>(maze: &mut MazeMap<Random>) -> Point
+where
+    Random: Rng,
+{
+    let i = maze.random.gen_range(0..maze.row().div_ceil(2));
+    let j = maze.random.gen_range(0..lattice_columns(maze));
+    let p = Point(i as i32 * 2, j as i32 * 2);
+    if !maze.is_outside(p) {
+        return p;
+    }
+    let cells = lattice_cells(maze);
+    if cells.is_empty() {
+        return p;
+    }
+    cells[maze.random.gen_range(0..cells.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{maze_options::MazeOptions, wrap::Wrap};
+
+    use super::*;
+
+    fn all_generators() -> Vec<Generator> {
+        vec![
+            Generator::RecursiveBacktracker,
+            Generator::Prim,
+            Generator::Kruskal,
+            Generator::HuntAndKill,
+            Generator::BinaryTree,
+            Generator::Sidewinder,
+            Generator::GrowingTree(GrowingTreeStrategy::Newest),
+            Generator::GrowingTree(GrowingTreeStrategy::Oldest),
+            Generator::GrowingTree(GrowingTreeStrategy::Random),
+            Generator::GrowingTree(GrowingTreeStrategy::Middle),
+            Generator::GrowingTree(GrowingTreeStrategy::Mixed(50)),
+            Generator::Wilson,
+            Generator::AldousBroder,
+            Generator::Eller,
+            Generator::RecursiveDivision(DivisionOptions::default()),
+            Generator::RecursiveDivision(DivisionOptions {
+                horizontal_bias: 80,
+                ..Default::default()
+            }),
+            Generator::Dungeon(DungeonOptions::default()),
+            Generator::Weave(WeaveOptions::default()),
+        ]
+    }
+
+    #[test]
+    fn test_generators_solvable() {
+        for generator in all_generators() {
+            for seed in 0..5 {
+                let random = StdRng::seed_from_u64(seed);
+                let maze = MazeMap::new_with_generator(15, 21, random, generator).unwrap();
+                let path = maze.solve(maze.st).unwrap();
+                assert_eq!(*path.last().unwrap(), maze.ed, "{}", generator);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lattice_generators_perfect() {
+        for generator in all_generators().into_iter().skip(1) {
+            if let Generator::Dungeon(_) | Generator::Weave(_) = generator {
+                continue;
+            }
+            let random = StdRng::seed_from_u64(7);
+            let maze = MazeMap::new_with_generator(11, 13, random, generator).unwrap();
+            let roads = (0..maze.row())
+                .flat_map(|i| (0..maze.column()).map(move |j| Point(i as i32, j as i32)))
+                .filter(|&p| maze[p] != MapValue::Wall)
+                .count();
+            // 完美迷宫：6 * 7 个格子组成一棵树，共 6 * 7 - 1 条通道
+            assert_eq!(roads, 6 * 7 + 6 * 7 - 1, "{}", generator);
+        }
+    }
+
+    #[test]
+    fn test_uniform_spanning_tree() {
+        // 2 * 2 个格子只有 4 棵生成树，分别缺少一面墙
+        let walls = [Point(0, 1), Point(1, 0), Point(1, 2), Point(2, 1)];
+        for generator in [Generator::Wilson, Generator::AldousBroder] {
+            let mut counts = [0; 4];
+            for seed in 0..4000 {
+                let random = StdRng::seed_from_u64(seed);
+                let maze = MazeMap::new_with_generator(3, 3, random, generator).unwrap();
+                let index = walls.iter().position(|&w| maze[w] == MapValue::Wall);
+                counts[index.unwrap()] += 1;
+            }
+            for count in counts {
+                assert!((850..1150).contains(&count), "{} {:?}", generator, counts);
+            }
+        }
+    }
+
+    #[test]
+    fn test_division_rooms() {
+        let options = DivisionOptions {
+            room_chance: 100,
+            room_size: 3,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(3);
+        let generator = Generator::RecursiveDivision(options);
+        let maze = MazeMap::new_with_generator(21, 21, random, generator).unwrap();
+        // 空旷的房间里存在四周全是路的墙角点
+        let open_corner = (0..maze.row() as i32)
+            .flat_map(|i| (0..maze.column() as i32).map(move |j| Point(i, j)))
+            .any(|p| p.0 % 2 == 1 && p.1 % 2 == 1 && maze[p] == MapValue::Road);
+        assert!(open_corner);
+        maze.solve(maze.st).unwrap();
+    }
+
+    #[test]
+    fn test_dungeon_rooms() {
+        for prune in [true, false] {
+            let options = DungeonOptions {
+                prune,
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(11);
+            let generator = Generator::Dungeon(options);
+            let maze = MazeMap::new_with_generator(31, 41, random, generator).unwrap();
+            assert!(!maze.rooms.is_empty());
+            for (i, room) in maze.rooms.iter().enumerate() {
+                assert!(room.points().iter().all(|&p| maze[p] != MapValue::Wall));
+                assert!(maze.rooms[i + 1..].iter().all(|r| !r.intersects(room)));
+                // 每个房间都能走到终点
+                maze.solve(room.center()).unwrap();
+            }
+            if prune {
+                let corridor_dead_ends = maze
+                    .dead_ends()
+                    .into_iter()
+                    .filter(|&p| !maze.rooms.iter().any(|r| r.contains(p)))
+                    .count();
+                assert_eq!(corridor_dead_ends, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weave_crossings() {
+        let generator = Generator::Weave(WeaveOptions { density: 100 });
+        for seed in 0..5 {
+            let random = StdRng::seed_from_u64(seed);
+            let maze = MazeMap::new_with_generator(21, 31, random, generator).unwrap();
+            let crossings: Vec<Point> = (0..maze.row() as i32)
+                .flat_map(|i| (0..maze.column() as i32).map(move |j| Point(i, j)))
+                .filter(|&p| maze[p] == MapValue::Crossing)
+                .collect();
+            assert!(!crossings.is_empty());
+            // 从交叉点出发也能到达终点，且途中经过交叉点时都是直行
+            let starts = [vec![maze.st], crossings].concat();
+            for p in starts {
+                let path = maze.solve(p).unwrap();
+                for w in path.windows(3) {
+                    if maze[w[1]] == MapValue::Crossing {
+                        assert_eq!(w[1] - w[0], w[2] - w[1]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        let cases = [(Wrap::Cylinder, 12), (Wrap::Torus, 12), (Wrap::Mobius, 11)];
+        for (wrap, row) in cases {
+            for generator in all_generators() {
+                let options = MazeOptions {
+                    generator,
+                    wrap,
+                    ..Default::default()
+                };
+                let random = StdRng::seed_from_u64(9);
+                let maze = MazeMap::new_with_options(row, 16, random, options).unwrap();
+                let path = maze.solve(maze.st).unwrap();
+                assert_eq!(*path.last().unwrap(), maze.ed, "{} {}", wrap, generator);
+                for w in path.windows(2) {
+                    assert!(
+                        maze.neighbours(w[0]).contains(&w[1]),
+                        "{} {}",
+                        wrap,
+                        generator
+                    );
+                }
+            }
+            // Kruskal 会用到接缝上的墙，格子仍然组成一棵树
+            let options = MazeOptions {
+                generator: Generator::Kruskal,
+                wrap,
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(9);
+            let maze = MazeMap::new_with_options(row, 16, random, options).unwrap();
+            let cells = row.div_ceil(2) * 8;
+            let roads = maze.map().iter().filter(|v| v.is_passable()).count();
+            assert_eq!(roads, cells * 2 - 1, "{}", wrap);
+            assert!(!maze.seams().is_empty(), "{}", wrap);
+        }
+        let options = MazeOptions {
+            wrap: Wrap::Cylinder,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(9);
+        assert!(MazeMap::new_with_options(12, 15, random, options).is_err());
+    }
+
+    #[test]
+    fn test_parse_generator() {
+        for generator in all_generators() {
+            assert_eq!(generator.to_string().parse::<Generator>(), Ok(generator));
+        }
+        assert!("unknown".parse::<Generator>().is_err());
+        assert!("prim:newest".parse::<Generator>().is_err());
+        assert_eq!(
+            "division:bias=70,rooms=20".parse::<Generator>(),
+            Ok(Generator::RecursiveDivision(DivisionOptions {
+                horizontal_bias: 70,
+                room_chance: 20,
+                ..Default::default()
+            }))
+        );
+    }
+}
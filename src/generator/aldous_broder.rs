@@ -0,0 +1,38 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, maze_map::MazeMap};
+
+use super::{
+    carve_passage, is_carved, lattice_components, lattice_neighbours, random_cell, MazeGenerator,
+};
+
+// 纯随机游走，均匀但很慢，作为 Wilson 的参照
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct AldousBroder;
+
+impl<Random> MazeGenerator<Random> for AldousBroder
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut p = random_cell(maze);
+        // 遮罩可能把格子分成互不相通的几块，逐块游走
+        for component in lattice_components(maze) {
+            if !component.contains(&p) {
+                p = component[maze.random.gen_range(0..component.len())];
+            }
+            let mut remaining = component.len() - 1;
+            carve_passage(maze, p, p);
+            while remaining > 0 {
+                let neighbours = lattice_neighbours(maze, p);
+                let to = neighbours[maze.random.gen_range(0..neighbours.len())];
+                if !is_carved(maze, to) {
+                    carve_passage(maze, p, to);
+                    remaining -= 1;
+                }
+                p = to;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, maze_map::MazeMap, point::Point};
+
+use super::{carve_passage, lattice_cells, lattice_neighbours, MazeGenerator};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct BinaryTree;
+
+impl<Random> MazeGenerator<Random> for BinaryTree
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        for cell in lattice_cells(maze) {
+            // 每个格子向上或向右打通一条路
+            let candidates: Vec<Point> = lattice_neighbours(maze, cell)
+                .into_iter()
+                .filter(|&p| p == cell - (2, 0) || p == cell + (0, 2))
+                .collect();
+            if candidates.is_empty() {
+                carve_passage(maze, cell, cell);
+                continue;
+            }
+            let next = candidates[maze.random.gen_range(0..candidates.len())];
+            carve_passage(maze, cell, next);
+        }
+        Ok(())
+    }
+}
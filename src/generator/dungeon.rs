@@ -0,0 +1,236 @@
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    disjoint_set::DisjointSet, errors::MazeResult, grid::Grid, map_value::MapValue,
+    maze_map::MazeMap, point::Point, random::randarray, rect::Rect,
+};
+
+use super::{
+    carve_passage, is_carved, lattice_cells, lattice_columns, lattice_neighbours, lattice_wall,
+    parse_key_values, parse_value, MazeGenerator,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DungeonOptions {
+    pub room_attempts: usize,
+    // 房间边长的范围，以格子为单位
+    pub min_room: usize,
+    pub max_room: usize,
+    // 房间与已经连通的区域之间再开一扇门的百分比概率
+    pub extra_doors: u8,
+    pub prune: bool,
+}
+
+impl Default for DungeonOptions {
+    fn default() -> Self {
+        Self {
+            room_attempts: 30,
+            min_room: 2,
+            max_room: 4,
+            extra_doors: 10,
+            prune: true,
+        }
+    }
+}
+
+impl Display for DungeonOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rooms={},min={},max={},doors={},prune={}",
+            self.room_attempts, self.min_room, self.max_room, self.extra_doors, self.prune
+        )
+    }
+}
+
+impl FromStr for DungeonOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut options = Self::default();
+        for (key, value) in parse_key_values(s)? {
+            match key {
+                "rooms" => options.room_attempts = parse_value(key, value)?,
+                "min" => options.min_room = parse_value(key, value)?,
+                "max" => options.max_room = parse_value(key, value)?,
+                "doors" => options.extra_doors = parse_value(key, value)?,
+                "prune" => options.prune = parse_value(key, value)?,
+                _ => return Err(format!("unknown dungeon option: {}", key)),
+            }
+        }
+        if options.min_room == 0 || options.min_room > options.max_room || options.extra_doors > 100
+        {
+            return Err(format!("invalid dungeon options: {}", s));
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Dungeon {
+    pub options: DungeonOptions,
+}
+
+impl Dungeon {
+    pub fn new(options: DungeonOptions) -> Self {
+        Self { options }
+    }
+
+    fn place_rooms<Random>(&self, maze: &mut MazeMap<Random>, region: &mut Grid<Option<usize>>)
+    where
+        Random: Rng,
+    {
+        let (rows, columns) = (maze.row().div_ceil(2), lattice_columns(maze));
+        let (min, max) = (self.options.min_room.max(1), self.options.max_room);
+        for _ in 0..self.options.room_attempts {
+            let height = maze.random.gen_range(min..=max.max(min)).min(rows);
+            let width = maze.random.gen_range(min..=max.max(min)).min(columns);
+            let top = maze.random.gen_range(0..=rows - height) as i32;
+            let left = maze.random.gen_range(0..=columns - width) as i32;
+            let room = Rect::new(
+                top * 2,
+                left * 2,
+                height as i32 * 2 - 1,
+                width as i32 * 2 - 1,
+            );
+            // 房间之间至少留出一个格子给走廊
+            let margin = Rect::new(room.top - 2, room.left - 2, room.height + 4, room.width + 4);
+            if maze.rooms.iter().any(|other| margin.intersects(other)) {
+                continue;
+            }
+            if room.points().into_iter().any(|p| maze.is_outside(p)) {
+                continue;
+            }
+            let id = maze.rooms.len();
+            for p in room.points() {
+                maze.set(p, MapValue::Road);
+                region[p] = Some(id);
+            }
+            maze.rooms.push(room);
+        }
+    }
+
+    fn fill_corridors<Random>(
+        &self,
+        maze: &mut MazeMap<Random>,
+        region: &mut Grid<Option<usize>>,
+    ) -> usize
+    where
+        Random: Rng,
+    {
+        let mut regions = maze.rooms.len();
+        for cell in lattice_cells(maze) {
+            if is_carved(maze, cell) {
+                continue;
+            }
+            carve_passage(maze, cell, cell);
+            region[cell] = Some(regions);
+            let mut stack = vec![cell];
+            while let Some(&p) = stack.last() {
+                let unvisited: Vec<Point> = lattice_neighbours(maze, p)
+                    .into_iter()
+                    .filter(|&next| !is_carved(maze, next))
+                    .collect();
+                if unvisited.is_empty() {
+                    stack.pop();
+                    continue;
+                }
+                let next = unvisited[maze.random.gen_range(0..unvisited.len())];
+                carve_passage(maze, p, next);
+                let wall = lattice_wall(maze, p, next);
+                for q in [wall, next] {
+                    region[q] = Some(regions);
+                }
+                stack.push(next);
+            }
+            regions += 1;
+        }
+        regions
+    }
+
+    fn connect<Random>(
+        &self,
+        maze: &mut MazeMap<Random>,
+        region: &Grid<Option<usize>>,
+        regions: usize,
+    ) where
+        Random: Rng,
+    {
+        let mut connectors = vec![];
+        for i in 0..maze.row() as i32 {
+            for j in 0..maze.column() as i32 {
+                let p = Point(i, j);
+                if (i + j) % 2 == 0 || maze[p] != MapValue::Wall {
+                    continue;
+                }
+                let (a, b) = if i % 2 == 1 {
+                    (p - (1, 0), p + (1, 0))
+                } else {
+                    (p - (0, 1), p + (0, 1))
+                };
+                if maze.is_overrange(a) || maze.is_overrange(b) {
+                    continue;
+                }
+                let (ra, rb) = (region[a], region[b]);
+                if let (Some(ra), Some(rb)) = (ra, rb) {
+                    if ra != rb {
+                        connectors.push((p, ra, rb));
+                    }
+                }
+            }
+        }
+        let connectors = randarray(&mut maze.random, &connectors);
+        let rooms = maze.rooms.len();
+        let mut sets = DisjointSet::new(regions);
+        for (p, ra, rb) in connectors {
+            if sets.union(ra, rb) {
+                maze.set(p, MapValue::Road);
+                continue;
+            }
+            // 额外的门只开在房间上，走廊之间不形成回路
+            if (ra < rooms || rb < rooms)
+                && maze.random.gen_range(0..100) < self.options.extra_doors
+            {
+                maze.set(p, MapValue::Road);
+            }
+        }
+    }
+
+    fn prune<Random>(&self, maze: &mut MazeMap<Random>)
+    where
+        Random: Rng,
+    {
+        let in_room = |maze: &MazeMap<Random>, p: Point| maze.rooms.iter().any(|r| r.contains(p));
+        let mut queue: VecDeque<Point> = maze.dead_ends().into_iter().collect();
+        while let Some(p) = queue.pop_front() {
+            if !maze.is_dead_end(p) || in_room(maze, p) {
+                continue;
+            }
+            maze.set(p, MapValue::Wall);
+            for q in p.get_range_vec() {
+                if !maze.is_overrange(q) && maze.is_dead_end(q) {
+                    queue.push_back(q);
+                }
+            }
+        }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for Dungeon
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut region = Grid::new(maze.row(), maze.column(), None);
+        self.place_rooms(maze, &mut region);
+        let regions = self.fill_corridors(maze, &mut region);
+        self.connect(maze, &region, regions);
+        // 没有房间时剪掉死路会把整个迷宫剪光
+        if self.options.prune && !maze.rooms.is_empty() {
+            self.prune(maze);
+        }
+        Ok(())
+    }
+}
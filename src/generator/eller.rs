@@ -0,0 +1,205 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::Rng;
+
+use crate::{errors::MazeResult, map_value::MapValue, maze_map::MazeMap, point::Point};
+
+use super::MazeGenerator;
+
+// 逐行生成迷宫，只保存当前一行格子的集合编号
+// 输出的每一行与 MazeMap 的格子布局一致：偶数行为格子行，奇数行为向下的通道行
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EllerRows<Random>
+where
+    Random: Rng,
+{
+    random: Random,
+    state: EllerState,
+}
+
+impl<Random> EllerRows<Random>
+where
+    Random: Rng,
+{
+    pub fn new(column: usize, random: Random) -> Self {
+        Self {
+            random,
+            state: EllerState::new(None, column),
+        }
+    }
+
+    pub fn with_rows(row: usize, column: usize, random: Random) -> Self {
+        Self {
+            random,
+            state: EllerState::new(Some(row), column),
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        self.state.column
+    }
+}
+
+// 生成的状态，随机数生成器由调用者传入，生成时可以一边生成一边写入地图
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct EllerState {
+    column: usize,
+    // None 表示无限生成
+    row: Option<usize>,
+    emitted: usize,
+    sets: Vec<usize>,
+    // 每个集合包含的格子，合并时把小的集合并入大的集合
+    members: HashMap<usize, Vec<usize>>,
+    next_set: usize,
+    pending: VecDeque<Vec<MapValue>>,
+}
+
+impl EllerState {
+    fn new(row: Option<usize>, column: usize) -> Self {
+        Self {
+            column,
+            row,
+            emitted: 0,
+            sets: vec![],
+            members: HashMap::new(),
+            next_set: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn cells(&self) -> usize {
+        self.column.div_ceil(2)
+    }
+
+    fn fresh_set(&mut self) -> usize {
+        self.next_set += 1;
+        self.next_set
+    }
+
+    fn merge(&mut self, a: usize, b: usize) {
+        let (keep, gone) = if self.members[&a].len() >= self.members[&b].len() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let moved = self.members.remove(&gone).unwrap_or_default();
+        for &i in moved.iter() {
+            self.sets[i] = keep;
+        }
+        self.members.entry(keep).or_default().extend(moved);
+    }
+
+    fn is_last(&self) -> bool {
+        match self.row {
+            Some(row) => self.emitted + 2 >= row,
+            None => false,
+        }
+    }
+
+    fn push_rows(&mut self, random: &mut impl Rng) {
+        let cells = self.cells();
+        if self.sets.is_empty() {
+            self.sets = vec![0; cells];
+        }
+        self.members.clear();
+        for i in 0..cells {
+            if self.sets[i] == 0 {
+                self.sets[i] = self.fresh_set();
+            }
+            self.members.entry(self.sets[i]).or_default().push(i);
+        }
+        let last = self.is_last();
+
+        let mut cell_row = vec![MapValue::Wall; self.column];
+        for i in 0..cells {
+            cell_row[i * 2] = MapValue::Road;
+        }
+        for i in 0..cells.saturating_sub(1) {
+            let (a, b) = (self.sets[i], self.sets[i + 1]);
+            if a == b {
+                continue;
+            }
+            if last || random.gen_bool(0.5) {
+                self.merge(a, b);
+                cell_row[i * 2 + 1] = MapValue::Road;
+            }
+        }
+        self.pending.push_back(cell_row);
+        if last {
+            return;
+        }
+
+        // 每个集合至少有一个格子向下打通
+        let mut down_row = vec![MapValue::Wall; self.column];
+        let mut next_sets = vec![0; cells];
+        let mut groups: Vec<Vec<usize>> = vec![];
+        let mut group_index = HashMap::new();
+        for i in 0..cells {
+            let index = *group_index.entry(self.sets[i]).or_insert_with(|| {
+                groups.push(vec![]);
+                groups.len() - 1
+            });
+            groups[index].push(i);
+        }
+        for members in groups {
+            let forced = members[random.gen_range(0..members.len())];
+            for &i in members.iter() {
+                if i == forced || random.gen_bool(0.5) {
+                    down_row[i * 2] = MapValue::Road;
+                    next_sets[i] = self.sets[i];
+                }
+            }
+        }
+        self.sets = next_sets;
+        self.pending.push_back(down_row);
+    }
+
+    fn next_row(&mut self, random: &mut impl Rng) -> Option<Vec<MapValue>> {
+        if let Some(row) = self.row {
+            if self.emitted >= row {
+                return None;
+            }
+        }
+        if self.pending.is_empty() {
+            if self.is_last() && self.emitted % 2 == 1 {
+                // 偶数行高的迷宫最后一行只能是墙
+                self.pending.push_back(vec![MapValue::Wall; self.column]);
+            } else {
+                self.push_rows(random);
+            }
+        }
+        self.emitted += 1;
+        self.pending.pop_front()
+    }
+}
+
+impl<Random> Iterator for EllerRows<Random>
+where
+    Random: Rng,
+{
+    type Item = Vec<MapValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.state.next_row(&mut self.random)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Eller;
+
+impl<Random> MazeGenerator<Random> for Eller
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut state = EllerState::new(Some(maze.row()), maze.column());
+        let mut i = 0;
+        while let Some(line) = state.next_row(&mut maze.random) {
+            for (j, value) in line.into_iter().enumerate() {
+                maze.set(Point(i, j as i32), value);
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+}
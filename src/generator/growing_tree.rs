@@ -0,0 +1,114 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{errors::MazeResult, events::GenerationEvent, maze_map::MazeMap, point::Point};
+
+use super::{carve_passage, is_carved, lattice_neighbours, random_cell, MazeGenerator};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum GrowingTreeStrategy {
+    #[default]
+    Newest,
+    Oldest,
+    Random,
+    Middle,
+    // 以百分比的概率选择最新的格子，否则随机选择
+    Mixed(u8),
+}
+
+impl Display for GrowingTreeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Newest => write!(f, "newest"),
+            Self::Oldest => write!(f, "oldest"),
+            Self::Random => write!(f, "random"),
+            Self::Middle => write!(f, "middle"),
+            Self::Mixed(percent) => write!(f, "mixed={}", percent),
+        }
+    }
+}
+
+impl FromStr for GrowingTreeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let strategy = match s.to_ascii_lowercase().as_str() {
+            "newest" => Self::Newest,
+            "oldest" => Self::Oldest,
+            "random" => Self::Random,
+            "middle" => Self::Middle,
+            "mixed" => Self::Mixed(50),
+            other => {
+                let percent = other
+                    .strip_prefix("mixed=")
+                    .and_then(|v| v.parse::<u8>().ok())
+                    .filter(|&v| v <= 100);
+                match percent {
+                    Some(percent) => Self::Mixed(percent),
+                    None => return Err(format!("unknown growing tree strategy: {}", s)),
+                }
+            }
+        };
+        Ok(strategy)
+    }
+}
+
+impl GrowingTreeStrategy {
+    fn pick(&self, random: &mut impl Rng, len: usize) -> usize {
+        match self {
+            Self::Newest => len - 1,
+            Self::Oldest => 0,
+            Self::Random => random.gen_range(0..len),
+            Self::Middle => len / 2,
+            Self::Mixed(percent) => {
+                if random.gen_range(0..100) < *percent {
+                    len - 1
+                } else {
+                    random.gen_range(0..len)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct GrowingTree {
+    pub strategy: GrowingTreeStrategy,
+}
+
+impl GrowingTree {
+    pub fn new(strategy: GrowingTreeStrategy) -> Self {
+        Self { strategy }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for GrowingTree
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let start = random_cell(maze);
+        carve_passage(maze, start, start);
+        let mut active = vec![start];
+        while !active.is_empty() {
+            let index = self.strategy.pick(&mut maze.random, active.len());
+            let cell = active[index];
+            let unvisited: Vec<Point> = lattice_neighbours(maze, cell)
+                .into_iter()
+                .filter(|&p| !is_carved(maze, p))
+                .collect();
+            if unvisited.is_empty() {
+                active.remove(index);
+                if let Some(&back) = active.last() {
+                    maze.emit(GenerationEvent::Backtrack(back));
+                }
+                continue;
+            }
+            let next = unvisited[maze.random.gen_range(0..unvisited.len())];
+            carve_passage(maze, cell, next);
+            active.push(next);
+        }
+        Ok(())
+    }
+}
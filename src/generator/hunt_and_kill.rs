@@ -0,0 +1,69 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, events::GenerationEvent, maze_map::MazeMap, point::Point};
+
+use super::{
+    carve_passage, is_carved, lattice_cells, lattice_neighbours, random_cell, MazeGenerator,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct HuntAndKill;
+
+impl HuntAndKill {
+    // 按行扫描，找到第一个与已开辟格子相邻的未开辟格子
+    // start 之前的格子都已开辟，下次从第一个未开辟的格子开始扫描
+    fn hunt<Random>(maze: &mut MazeMap<Random>, cells: &[Point], start: &mut usize) -> Option<Point>
+    where
+        Random: Rng,
+    {
+        while *start < cells.len() && is_carved(maze, cells[*start]) {
+            *start += 1;
+        }
+        for &cell in &cells[*start..] {
+            if is_carved(maze, cell) {
+                continue;
+            }
+            maze.emit(GenerationEvent::Consider(cell));
+            let carved: Vec<Point> = lattice_neighbours(maze, cell)
+                .into_iter()
+                .filter(|&p| is_carved(maze, p))
+                .collect();
+            if carved.is_empty() {
+                continue;
+            }
+            let from = carved[maze.random.gen_range(0..carved.len())];
+            carve_passage(maze, from, cell);
+            return Some(cell);
+        }
+        None
+    }
+}
+
+impl<Random> MazeGenerator<Random> for HuntAndKill
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let cells = lattice_cells(maze);
+        let mut start = 0;
+        let mut current = random_cell(maze);
+        carve_passage(maze, current, current);
+        loop {
+            let unvisited: Vec<Point> = lattice_neighbours(maze, current)
+                .into_iter()
+                .filter(|&p| !is_carved(maze, p))
+                .collect();
+            if !unvisited.is_empty() {
+                let next = unvisited[maze.random.gen_range(0..unvisited.len())];
+                carve_passage(maze, current, next);
+                current = next;
+                continue;
+            }
+            match Self::hunt(maze, &cells, &mut start) {
+                Some(cell) => current = cell,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use rand::Rng;
+
+use crate::{
+    disjoint_set::DisjointSet, errors::MazeResult, events::GenerationEvent, maze_map::MazeMap,
+    random::shuffle,
+};
+
+use super::{
+    carve_passage, lattice_cells, lattice_edges, lattice_index, lattice_len, lattice_wall,
+    MazeGenerator,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Kruskal;
+
+impl<Random> MazeGenerator<Random> for Kruskal
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let cells = lattice_cells(maze);
+        let mut edges = lattice_edges(maze);
+        shuffle(&mut maze.random, &mut edges);
+        let mut sets = DisjointSet::new(lattice_len(maze));
+        for cell in cells {
+            carve_passage(maze, cell, cell);
+        }
+        for (a, b) in edges {
+            maze.emit(GenerationEvent::Consider(lattice_wall(maze, a, b)));
+            if sets.union(lattice_index(maze, a), lattice_index(maze, b)) {
+                carve_passage(maze, a, b);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use rand::Rng;
+
+use crate::{
+    errors::MazeResult, events::GenerationEvent, grid::Grid, maze_map::MazeMap, point::Point,
+};
+
+use super::{carve_passage, is_carved, lattice_neighbours, random_cell, MazeGenerator};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Prim;
+
+impl Prim {
+    fn add_frontier<Random>(
+        maze: &MazeMap<Random>,
+        frontier: &mut Vec<Point>,
+        in_frontier: &mut Grid<bool>,
+        p: Point,
+    ) where
+        Random: Rng,
+    {
+        for next in lattice_neighbours(maze, p) {
+            if is_carved(maze, next) || in_frontier[next] {
+                continue;
+            }
+            in_frontier[next] = true;
+            frontier.push(next);
+        }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for Prim
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut in_frontier = Grid::new(maze.row(), maze.column(), false);
+        let mut frontier = vec![];
+        let start = random_cell(maze);
+        carve_passage(maze, start, start);
+        Self::add_frontier(maze, &mut frontier, &mut in_frontier, start);
+        while !frontier.is_empty() {
+            let index = maze.random.gen_range(0..frontier.len());
+            let cell = frontier.swap_remove(index);
+            maze.emit(GenerationEvent::Consider(cell));
+            let carved: Vec<Point> = lattice_neighbours(maze, cell)
+                .into_iter()
+                .filter(|&p| is_carved(maze, p))
+                .collect();
+            let from = carved[maze.random.gen_range(0..carved.len())];
+            carve_passage(maze, from, cell);
+            Self::add_frontier(maze, &mut frontier, &mut in_frontier, cell);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+use rand::Rng;
+
+use crate::{
+    errors::MazeResult, events::GenerationEvent, map_value::MapValue, maze_map::MazeMap,
+    point::Point, random::randarray,
+};
+
+use super::MazeGenerator;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct RecursiveBacktracker;
+
+impl RecursiveBacktracker {
+    fn get_walls<Random>(maze: &MazeMap<Random>, p: Point, lp: Point) -> Vec<Point>
+    where
+        Random: Rng,
+    {
+        let mut result = vec![];
+        for point in maze.neighbours(p) {
+            if maze.is_outside(point) {
+                continue;
+            }
+            if point == lp {
+                continue;
+            }
+            result.push(point);
+        }
+        result
+    }
+
+    fn check_walls<Random>(maze: &MazeMap<Random>, p: Point, lp: Point) -> bool
+    where
+        Random: Rng,
+    {
+        let temp = Self::get_walls(maze, p, lp);
+        if temp.is_empty() {
+            return false;
+        }
+        for t in temp {
+            if maze[t] != MapValue::Wall {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<Random> MazeGenerator<Random> for RecursiveBacktracker
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut stack = VecDeque::new();
+        stack.push_back((maze.inst_st, Point(-1, -1)));
+        let mut last = Point(-1, -1);
+        while let Some((p, lp)) = stack.pop_back() {
+            maze.emit(GenerationEvent::Consider(p));
+            if !Self::check_walls(maze, p, lp) {
+                continue;
+            }
+            // 不是从上一个打通的位置继续时，说明回溯到了 lp
+            if lp != last {
+                maze.emit(GenerationEvent::Backtrack(lp));
+            }
+            last = p;
+            maze.set(p, MapValue::Road);
+            let mut around_walls = Self::get_walls(maze, p, lp);
+            if around_walls.is_empty() {
+                continue;
+            }
+            around_walls = randarray(&mut maze.random, &around_walls);
+            for wall in around_walls {
+                stack.push_back((wall, p));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{errors::MazeResult, map_value::MapValue, maze_map::MazeMap, point::Point};
+
+use super::{lattice_columns, parse_key_values, parse_value, MazeGenerator};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DivisionOptions {
+    // 分割后每个房间在分割方向上至少包含的格子数
+    pub min_chamber: usize,
+    // 水平分割的倾向，50 时按房间长宽比选择方向，100 时总是水平分割
+    pub horizontal_bias: u8,
+    // 不超过 room_size 的房间以该百分比概率保持空旷
+    pub room_chance: u8,
+    pub room_size: usize,
+}
+
+impl Default for DivisionOptions {
+    fn default() -> Self {
+        Self {
+            min_chamber: 1,
+            horizontal_bias: 50,
+            room_chance: 0,
+            room_size: 4,
+        }
+    }
+}
+
+impl Display for DivisionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min={},bias={},rooms={},room-size={}",
+            self.min_chamber, self.horizontal_bias, self.room_chance, self.room_size
+        )
+    }
+}
+
+impl FromStr for DivisionOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut options = Self::default();
+        for (key, value) in parse_key_values(s)? {
+            match key {
+                "min" => options.min_chamber = parse_value(key, value)?,
+                "bias" => options.horizontal_bias = parse_value(key, value)?,
+                "rooms" => options.room_chance = parse_value(key, value)?,
+                "room-size" => options.room_size = parse_value(key, value)?,
+                _ => return Err(format!("unknown division option: {}", key)),
+            }
+        }
+        if options.min_chamber == 0 || options.horizontal_bias > 100 || options.room_chance > 100 {
+            return Err(format!("invalid division options: {}", s));
+        }
+        Ok(options)
+    }
+}
+
+// 以格子为单位的矩形区域
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct Chamber {
+    top: usize,
+    left: usize,
+    height: usize,
+    width: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct RecursiveDivision {
+    pub options: DivisionOptions,
+}
+
+impl RecursiveDivision {
+    pub fn new(options: DivisionOptions) -> Self {
+        Self { options }
+    }
+
+    // 切分一个区域，返回切出的两个区域，不能再切时返回 None
+    fn divide<Random>(&self, maze: &mut MazeMap<Random>, chamber: Chamber) -> Option<[Chamber; 2]>
+    where
+        Random: Rng,
+    {
+        let min = self.options.min_chamber.max(1);
+        let can_horizontal = chamber.height >= 2 * min;
+        let can_vertical = chamber.width >= 2 * min;
+        if !can_horizontal && !can_vertical {
+            return None;
+        }
+        let room_size = self.options.room_size;
+        if chamber.height <= room_size
+            && chamber.width <= room_size
+            && maze.random.gen_range(0..100) < self.options.room_chance
+        {
+            return None;
+        }
+        let horizontal = match (can_horizontal, can_vertical) {
+            (true, true) => {
+                let bias = self.options.horizontal_bias as usize;
+                let weight_h = bias * chamber.height;
+                let weight_v = (100 - bias) * chamber.width;
+                maze.random.gen_range(0..(weight_h + weight_v).max(1)) < weight_h
+            }
+            (horizontal, _) => horizontal,
+        };
+        let Chamber {
+            top,
+            left,
+            height,
+            width,
+        } = chamber;
+        if horizontal {
+            let split = top + maze.random.gen_range(min..=height - min);
+            let gap = left + maze.random.gen_range(0..width);
+            let i = (split * 2 - 1) as i32;
+            for j in left * 2..(left + width) * 2 - 1 {
+                if j != gap * 2 {
+                    maze.set(Point(i, j as i32), MapValue::Wall);
+                }
+            }
+            Some([
+                Chamber {
+                    height: split - top,
+                    ..chamber
+                },
+                Chamber {
+                    top: split,
+                    height: top + height - split,
+                    ..chamber
+                },
+            ])
+        } else {
+            let split = left + maze.random.gen_range(min..=width - min);
+            let gap = top + maze.random.gen_range(0..height);
+            let j = (split * 2 - 1) as i32;
+            for i in top * 2..(top + height) * 2 - 1 {
+                if i != gap * 2 {
+                    maze.set(Point(i as i32, j), MapValue::Wall);
+                }
+            }
+            Some([
+                Chamber {
+                    width: split - left,
+                    ..chamber
+                },
+                Chamber {
+                    left: split,
+                    width: left + width - split,
+                    ..chamber
+                },
+            ])
+        }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for RecursiveDivision
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let height = maze.row().div_ceil(2);
+        let width = lattice_columns(maze);
+        // 从空地开始，只有格子范围以外的行列保持为墙
+        for i in 0..height * 2 - 1 {
+            for j in 0..width * 2 - 1 {
+                maze.set(Point(i as i32, j as i32), MapValue::Road);
+            }
+        }
+        // 用栈代替递归，先处理切出的第一个区域，顺序与递归时相同
+        let mut stack = vec![Chamber {
+            top: 0,
+            left: 0,
+            height,
+            width,
+        }];
+        while let Some(chamber) = stack.pop() {
+            if let Some([first, second]) = self.divide(maze, chamber) {
+                stack.push(second);
+                stack.push(first);
+            }
+        }
+        Ok(())
+    }
+}
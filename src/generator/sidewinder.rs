@@ -0,0 +1,39 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, maze_map::MazeMap, point::Point};
+
+use super::{carve_passage, MazeGenerator};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Sidewinder;
+
+impl<Random> MazeGenerator<Random> for Sidewinder
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let (row, column) = (maze.row() as i32, maze.column() as i32);
+        for i in (0..row).step_by(2) {
+            let mut run = vec![];
+            for j in (0..column).step_by(2) {
+                let cell = Point(i, j);
+                run.push(cell);
+                let at_east = j + 2 >= column;
+                // 第一行只能一路向右
+                let close_run = at_east || (i > 0 && maze.random.gen_bool(0.5));
+                if !close_run {
+                    carve_passage(maze, cell, cell + (0, 2));
+                    continue;
+                }
+                if i > 0 {
+                    let member = run[maze.random.gen_range(0..run.len())];
+                    carve_passage(maze, member, member - (2, 0));
+                } else {
+                    carve_passage(maze, cell, cell);
+                }
+                run.clear();
+            }
+        }
+        Ok(())
+    }
+}
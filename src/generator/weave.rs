@@ -0,0 +1,137 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    disjoint_set::DisjointSet, errors::MazeResult, map_value::MapValue, maze_map::MazeMap,
+    random::randarray,
+};
+
+use super::{
+    carve_passage, lattice_cells, lattice_edges, lattice_index, lattice_len, lattice_neighbours,
+    parse_key_values, parse_value, MazeGenerator,
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct WeaveOptions {
+    // 每个可以交叉的格子成为交叉点的百分比概率
+    pub density: u8,
+}
+
+impl Default for WeaveOptions {
+    fn default() -> Self {
+        Self { density: 50 }
+    }
+}
+
+impl Display for WeaveOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "density={}", self.density)
+    }
+}
+
+impl FromStr for WeaveOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut options = Self::default();
+        for (key, value) in parse_key_values(s)? {
+            match key {
+                "density" => options.density = parse_value(key, value)?,
+                _ => return Err(format!("unknown weave option: {}", key)),
+            }
+        }
+        if options.density > 100 {
+            return Err(format!("invalid weave options: {}", s));
+        }
+        Ok(options)
+    }
+}
+
+// 先随机放置交叉点，再用 Kruskal 连接其余的格子
+// 交叉点上横向和纵向的通道互不相通，分别把两侧的格子并入同一集合
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Weave {
+    pub options: WeaveOptions,
+}
+
+impl Weave {
+    pub fn new(options: WeaveOptions) -> Self {
+        Self { options }
+    }
+
+    fn place_crossings<Random>(&self, maze: &mut MazeMap<Random>, sets: &mut DisjointSet)
+    where
+        Random: Rng,
+    {
+        let cells = lattice_cells(maze);
+        let cells = randarray(&mut maze.random, &cells);
+        for cell in cells {
+            if maze.random.gen_range(0..100) >= self.options.density {
+                continue;
+            }
+            let (up, down) = (cell - (2, 0), cell + (2, 0));
+            let (left, right) = (cell - (0, 2), cell + (0, 2));
+            // 交叉点不放在环绕的接缝上，直行穿过时不需要换算坐标
+            let neighbours = lattice_neighbours(maze, cell);
+            if [up, down, left, right]
+                .iter()
+                .any(|p| !neighbours.contains(p))
+            {
+                continue;
+            }
+            // 交叉点本身不能已经有通道，相邻的格子也不能是交叉点
+            let used = cell
+                .get_range_vec()
+                .into_iter()
+                .any(|wall| maze[wall] != MapValue::Wall);
+            if used || maze[cell] == MapValue::Crossing {
+                continue;
+            }
+            if [up, down, left, right]
+                .iter()
+                .any(|&p| maze[p] == MapValue::Crossing)
+            {
+                continue;
+            }
+            let [u, d, l, r] = [up, down, left, right].map(|p| sets.find(lattice_index(maze, p)));
+            // 两条通道连接的集合不能重复，否则会形成回路
+            if u == d || l == r || (u == l && d == r) || (u == r && d == l) {
+                continue;
+            }
+            sets.union(u, d);
+            sets.union(l, r);
+            for next in [up, down, left, right] {
+                carve_passage(maze, cell, next);
+            }
+            maze.set(cell, MapValue::Crossing);
+        }
+    }
+}
+
+impl<Random> MazeGenerator<Random> for Weave
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let mut sets = DisjointSet::new(lattice_len(maze));
+        self.place_crossings(maze, &mut sets);
+        let cells = lattice_cells(maze);
+        let edges = lattice_edges(maze);
+        let edges = randarray(&mut maze.random, &edges);
+        for cell in cells {
+            if maze[cell] == MapValue::Wall {
+                carve_passage(maze, cell, cell);
+            }
+        }
+        for (a, b) in edges {
+            if maze[a] == MapValue::Crossing || maze[b] == MapValue::Crossing {
+                continue;
+            }
+            if sets.union(lattice_index(maze, a), lattice_index(maze, b)) {
+                carve_passage(maze, a, b);
+            }
+        }
+        Ok(())
+    }
+}
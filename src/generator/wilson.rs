@@ -0,0 +1,57 @@
+use rand::Rng;
+
+use crate::{errors::MazeResult, grid::Grid, maze_map::MazeMap, random::randarray};
+
+use super::{
+    carve_passage, is_carved, lattice_cells, lattice_components, lattice_neighbours, random_cell,
+    MazeGenerator,
+};
+
+// 循环擦除随机游走，在所有完美迷宫中均匀采样
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Wilson;
+
+impl<Random> MazeGenerator<Random> for Wilson
+where
+    Random: Rng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        let cells = lattice_cells(maze);
+        let root = random_cell(maze);
+        carve_passage(maze, root, root);
+        // 遮罩可能把格子分成互不相通的几块，每块都需要一个根，否则随机游走不会结束
+        for component in lattice_components(maze) {
+            if !component.iter().any(|&p| is_carved(maze, p)) {
+                let root = component[maze.random.gen_range(0..component.len())];
+                carve_passage(maze, root, root);
+            }
+        }
+        let order = randarray(&mut maze.random, &cells);
+        // 沿 next 走回时只会经过这一次游走离开过的格子，之前留下的值不用清空
+        let mut next = Grid::new(maze.row(), maze.column(), None);
+        for start in order {
+            if is_carved(maze, start) {
+                continue;
+            }
+            // 随机游走直到碰到已经在树上的格子，只记录每个格子最后一次离开的方向
+            let mut p = start;
+            while !is_carved(maze, p) {
+                let neighbours = lattice_neighbours(maze, p);
+                let to = neighbours[maze.random.gen_range(0..neighbours.len())];
+                next[p] = Some(to);
+                p = to;
+            }
+            let mut p = start;
+            loop {
+                let to = next[p].unwrap();
+                let reached = is_carved(maze, to);
+                carve_passage(maze, p, to);
+                if reached {
+                    break;
+                }
+                p = to;
+            }
+        }
+        Ok(())
+    }
+}
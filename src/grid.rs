@@ -0,0 +1,142 @@
+use std::{
+    ops::{Index, IndexMut},
+    slice::{ChunksExact, ChunksExactMut},
+};
+
+use crate::point::{CanPointIndex, Point};
+
+// 按行连续存放的二维表，整张地图只有一次分配
+// `grid[i]` 取得第 i 行，`grid[p]` 取得位置 p
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct Grid<T> {
+    row: usize,
+    column: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T>
+where
+    T: Clone,
+{
+    pub fn new(row: usize, column: usize, value: T) -> Self {
+        Self {
+            row,
+            column,
+            data: vec![value; row * column],
+        }
+    }
+
+    // 每行长度必须相同
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let row = rows.len();
+        let column = rows.first().map_or(0, |line| line.len());
+        if rows.iter().any(|line| line.len() != column) {
+            return None;
+        }
+        let data = rows.into_iter().flatten().collect();
+        Some(Self { row, column, data })
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<T>> {
+        self.rows().map(|line| line.to_vec()).collect()
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn is_overrange(&self, p: Point) -> bool {
+        p.0 < 0 || p.1 < 0 || p.0 as usize >= self.row || p.1 as usize >= self.column
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        if self.is_overrange(p) {
+            return None;
+        }
+        Some(&self[p])
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, T> {
+        self.data.chunks_exact(self.column.max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> ChunksExactMut<'_, T> {
+        self.data.chunks_exact_mut(self.column.max(1))
+    }
+
+    // 按行展开的全部元素
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    // 行优先的全部位置
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let column = self.column;
+        (0..self.row).flat_map(move |i| (0..column).map(move |j| Point(i as i32, j as i32)))
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = [T];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        let start = index * self.column;
+        &self.data[start..start + self.column]
+    }
+}
+
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let start = index * self.column;
+        &mut self.data[start..start + self.column]
+    }
+}
+
+impl<T, U> Index<Point<U>> for Grid<T>
+where
+    U: CanPointIndex,
+{
+    type Output = T;
+
+    fn index(&self, index: Point<U>) -> &Self::Output {
+        let (i, j) = (index.0.to_usize(), index.1.to_usize());
+        assert!(j < self.column, "column {} out of range {}", j, self.column);
+        &self.data[i * self.column + j]
+    }
+}
+
+impl<T, U> IndexMut<Point<U>> for Grid<T>
+where
+    U: CanPointIndex,
+{
+    fn index_mut(&mut self, index: Point<U>) -> &mut Self::Output {
+        let (i, j) = (index.0.to_usize(), index.1.to_usize());
+        assert!(j < self.column, "column {} out of range {}", j, self.column);
+        &mut self.data[i * self.column + j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::new(3, 4, 0);
+        grid[Point(1, 2)] = 5;
+        grid[2][3] = 7;
+        assert_eq!(grid[1], [0, 0, 5, 0]);
+        assert_eq!(grid.get(Point(2, 3)), Some(&7));
+        assert_eq!(grid.get(Point(3, 0)), None);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.points().nth(6), Some(Point(1, 2)));
+        assert_eq!(Grid::from_rows(grid.to_rows()), Some(grid));
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+
+use rand::{rngs::ThreadRng, Rng};
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    grid::Grid,
+    point::Point,
+    topology::Topology,
+};
+
+// 任意网格上的迷宫，每个格子记录向各个方向是否连通
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct GridMaze<T, Random = ThreadRng>
+where
+    T: Topology,
+    Random: Rng,
+{
+    pub random: Random,
+    pub topology: T,
+    pub links: Grid<u8>,
+    pub st: Point,
+    pub ed: Point,
+}
+
+impl<T, Random> GridMaze<T, Random>
+where
+    T: Topology,
+    Random: Rng,
+{
+    pub fn new_with_random(row: usize, column: usize, random: Random) -> MazeResult<Self> {
+        let mut self_ = Self {
+            random,
+            topology: T::default(),
+            links: Grid::default(),
+            st: Default::default(),
+            ed: Default::default(),
+        };
+        self_.generate(row, column)?;
+        Ok(self_)
+    }
+
+    pub fn row(&self) -> usize {
+        self.links.row()
+    }
+
+    pub fn column(&self) -> usize {
+        self.links.column()
+    }
+
+    pub fn is_overrange(&self, p: Point) -> bool {
+        self.links.is_overrange(p)
+    }
+
+    pub fn is_open(&self, p: Point, dir: T::Direction) -> bool {
+        !self.is_overrange(p) && self.links[p] & self.topology.bit(dir) != 0
+    }
+
+    // 范围内的邻居，不考虑是否连通
+    pub fn neighbours(&self, p: Point) -> Vec<(T::Direction, Point)> {
+        self.topology
+            .directions(p)
+            .into_iter()
+            .map(|dir| (dir, self.topology.neighbour(p, dir)))
+            .filter(|&(_, p2)| !self.is_overrange(p2))
+            .collect()
+    }
+
+    pub fn get_roads(&self, p: Point) -> Vec<Point> {
+        self.neighbours(p)
+            .into_iter()
+            .filter(|&(dir, _)| self.is_open(p, dir))
+            .map(|(_, p2)| p2)
+            .collect()
+    }
+
+    fn link(&mut self, p: Point, dir: T::Direction) {
+        let p2 = self.topology.neighbour(p, dir);
+        self.links[p] |= self.topology.bit(dir);
+        self.links[p2] |= self.topology.bit(self.topology.opposite(dir));
+    }
+
+    // 递归回溯，起点在左上角，终点在右下角
+    pub fn generate(&mut self, row: usize, column: usize) -> MazeResult<()> {
+        if row < 2 || column < 2 {
+            return Err(MazeError::Init(String::from("行和列不能小于2")));
+        }
+        if row > i32::MAX as usize || column > i32::MAX as usize {
+            return Err(MazeError::Init(String::from("行和列超出范围")));
+        }
+        let (row, column) = (row as i32, column as i32);
+        self.links = Grid::new(row as usize, column as usize, 0);
+        let mut visited = Grid::new(row as usize, column as usize, false);
+        let start = Point(
+            self.random.gen_range(0..row),
+            self.random.gen_range(0..column),
+        );
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(&p) = stack.last() {
+            let unvisited: Vec<(T::Direction, Point)> = self
+                .neighbours(p)
+                .into_iter()
+                .filter(|&(_, p2)| !visited[p2])
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+            let (dir, next) = unvisited[self.random.gen_range(0..unvisited.len())];
+            self.link(p, dir);
+            visited[next] = true;
+            stack.push(next);
+        }
+        self.st = Point(0, 0);
+        self.ed = Point(row - 1, column - 1);
+        Ok(())
+    }
+
+    pub fn re_generate(&mut self) -> MazeResult<()> {
+        self.generate(self.row(), self.column())
+    }
+
+    pub fn solve(&self, pos: Point) -> MazeResult<Vec<Point>> {
+        if self.is_overrange(pos) {
+            return Err(MazeError::SolveException);
+        }
+        let mut map_temp = Grid::new(self.row(), self.column(), None);
+        let mut queue = VecDeque::from([pos]);
+        map_temp[pos] = Some(pos);
+        while let Some(p) = queue.pop_front() {
+            if p == self.ed {
+                let mut res = vec![p];
+                let mut rp = p;
+                while rp != pos {
+                    rp = map_temp[rp].unwrap();
+                    res.push(rp);
+                }
+                res.reverse();
+                return Ok(res);
+            }
+            for road in self.get_roads(p) {
+                if map_temp[road].is_none() {
+                    map_temp[road] = Some(p);
+                    queue.push_back(road);
+                }
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+
+    // 与 `Game::move_to` 相同，沿着没有岔路的通道一直走
+    pub fn move_from(&self, pos: Point, dir: T::Direction) -> MazeResult<Vec<Point>> {
+        if !self.is_open(pos, dir) {
+            return Err(MazeError::CanNotMove);
+        }
+        let mut lp = pos;
+        let mut p = self.topology.neighbour(pos, dir);
+        let mut move_list = vec![lp, p];
+        while p != self.ed {
+            let roads: Vec<Point> = self
+                .get_roads(p)
+                .into_iter()
+                .filter(|&p2| p2 != lp)
+                .collect();
+            if roads.len() != 1 {
+                break;
+            }
+            lp = p;
+            p = roads[0];
+            move_list.push(p);
+        }
+        Ok(move_list)
+    }
+
+    pub fn render(&self, glyph: impl Fn(Point) -> char) -> Vec<String> {
+        self.topology.render(
+            self.row(),
+            self.column(),
+            |p, dir| self.is_open(p, dir),
+            glyph,
+        )
+    }
+
+    pub fn display(&self) -> String {
+        self.render(|p| {
+            if p == self.st {
+                'S'
+            } else if p == self.ed {
+                'E'
+            } else {
+                ' '
+            }
+        })
+        .join("\n")
+    }
+}
+
+impl<T, Random> GridMaze<T, Random>
+where
+    T: Topology,
+    Random: Rng + Default,
+{
+    pub fn new(row: usize, column: usize) -> MazeResult<Self> {
+        Self::new_with_random(row, column, Random::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::topology::{Hex, HexDirection, Triangle};
+
+    use super::*;
+
+    fn check_perfect<T>(maze: &GridMaze<T, StdRng>)
+    where
+        T: Topology,
+    {
+        let mut links = 0;
+        for i in 0..maze.row() as i32 {
+            for j in 0..maze.column() as i32 {
+                let p = Point(i, j);
+                let roads = maze.get_roads(p);
+                assert!(roads.len() <= maze.topology.directions(p).len());
+                // 连通关系是对称的
+                for p2 in roads.iter() {
+                    assert!(maze.get_roads(*p2).contains(&p));
+                }
+                links += roads.len();
+            }
+        }
+        assert_eq!(links / 2, maze.row() * maze.column() - 1);
+        let path = maze.solve(maze.st).unwrap();
+        assert_eq!((path[0], *path.last().unwrap()), (maze.st, maze.ed));
+    }
+
+    #[test]
+    fn test_hex() {
+        for seed in 0..5 {
+            let maze = GridMaze::<Hex, _>::new_with_random(7, 9, StdRng::seed_from_u64(seed));
+            check_perfect(&maze.unwrap());
+        }
+        let hex = Hex;
+        // 偶数行和奇数行的斜向邻居互为相反方向
+        for p in [Point(2, 3), Point(3, 3)] {
+            for dir in hex.directions(p) {
+                let back = hex.neighbour(hex.neighbour(p, dir), hex.opposite(dir));
+                assert_eq!(back, p);
+            }
+        }
+        let maze = GridMaze::<Hex, _>::new_with_random(3, 4, StdRng::seed_from_u64(1)).unwrap();
+        let lines = maze.render(|_| ' ');
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0].trim_end(), " / \\ / \\ / \\ / \\");
+        assert!(maze.move_from(maze.st, HexDirection::NorthEast).is_err());
+    }
+
+    #[test]
+    fn test_triangle() {
+        for seed in 0..5 {
+            let maze = GridMaze::<Triangle, _>::new_with_random(6, 11, StdRng::seed_from_u64(seed));
+            check_perfect(&maze.unwrap());
+        }
+        let triangle = Triangle;
+        assert_eq!(triangle.directions(Point(0, 0)).len(), 3);
+        for p in [Point(1, 1), Point(1, 2)] {
+            for dir in triangle.directions(p) {
+                let p2 = triangle.neighbour(p, dir);
+                assert!(triangle.directions(p2).contains(&triangle.opposite(dir)));
+            }
+        }
+    }
+}
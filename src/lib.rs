@@ -1,11 +1,37 @@
+pub mod braid;
 #[cfg(feature = "console")]
 pub mod console_game;
+#[cfg(feature = "console")]
+pub mod console_grid;
+#[cfg(feature = "console")]
+pub mod console_tower;
+pub mod difficulty;
+pub mod disjoint_set;
+pub mod distance;
 pub mod errors;
+pub mod events;
+pub mod frame;
 pub mod game;
 pub mod game_value;
+pub mod generator;
+pub mod grid;
+pub mod grid_maze;
 pub mod map_value;
+pub mod mask;
+pub mod maze_code;
 pub mod maze_map;
+pub mod maze_options;
 pub mod move_status;
+pub mod placement;
 pub mod player;
 pub mod point;
 pub mod random;
+pub mod rect;
+pub mod route;
+pub mod solver;
+pub mod stats;
+pub mod terrain;
+pub mod tiled;
+pub mod topology;
+pub mod tower;
+pub mod wrap;
@@ -1,22 +1,41 @@
-#![cfg(feature = "console")]
-
 use clap::{CommandFactory, FromArgMatches};
 use maze::{
-    console_game::{self, Cli, ConsoleGame, SubcommandNew},
+    console_game::{self, Cli, ConsoleGame, GridShape, SubcommandNew},
+    console_grid::ConsoleGrid,
+    console_tower::ConsoleTower,
     errors::MazeResult,
     game::Game,
+    topology::{Hex, Triangle},
 };
-use rand::{thread_rng, SeedableRng};
+use rand::{thread_rng, Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
+fn new_random(seed: Option<u64>) -> ChaCha8Rng {
+    match seed {
+        Some(state) => ChaCha8Rng::seed_from_u64(state),
+        None => ChaCha8Rng::from_rng(thread_rng()).unwrap(),
+    }
+}
+
 fn run() -> MazeResult<()> {
-    let cli;
-    if std::env::args().len() == 1 {
-        cli = Cli::New(SubcommandNew {
+    let cli = if std::env::args().len() == 1 {
+        Cli::New(SubcommandNew {
             row: 10,
             column: 20,
             seed: None,
-        });
+            code: None,
+            algo: None,
+            loops: None,
+            terrain: None,
+            mask: None,
+            no_mask: false,
+            wrap: None,
+            placement: None,
+            frame: false,
+            no_frame: false,
+            target: None,
+            attempts: 1000,
+        })
     } else {
         let mut matches = match console_game::Cli::command()
             .multicall(false)
@@ -24,38 +43,68 @@ fn run() -> MazeResult<()> {
         {
             Ok(v) => v,
             Err(e) => {
-                println!("{}", e.to_string());
+                println!("{}", e);
                 return Ok(());
             }
         };
-        cli = match console_game::Cli::from_arg_matches_mut(&mut matches) {
+        match console_game::Cli::from_arg_matches_mut(&mut matches) {
             Ok(v) => v,
             Err(e) => {
-                println!("{}", e.to_string());
+                println!("{}", e);
                 return Ok(());
             }
         }
-    }
-    let mut game = match cli {
-        Cli::New(SubcommandNew { row, column, seed }) => {
-            let random = match seed {
-                Some(state) => ChaCha8Rng::seed_from_u64(state),
-                None => ChaCha8Rng::from_rng(thread_rng()).unwrap(),
-            };
-            let game = ConsoleGame::new_with_random(row, column, random);
-            game
+    };
+    match cli {
+        Cli::New(sub) => {
+            if let Some(code) = &sub.code {
+                return ConsoleGame::<ChaCha8Rng>::from_code(code)?.run();
+            }
+            let options = sub.options(&Default::default())?;
+            let seed = sub.seed.unwrap_or_else(|| thread_rng().gen());
+            if let Some(target) = &sub.target {
+                let (mut game, seed) = ConsoleGame::<ChaCha8Rng>::new_with_difficulty(
+                    sub.row,
+                    sub.column,
+                    seed,
+                    options,
+                    target,
+                    sub.attempts,
+                )?;
+                println!("seed: {}", seed);
+                return game.run();
+            }
+            ConsoleGame::<ChaCha8Rng>::new_with_seed(sub.row, sub.column, seed, options)?.run()
+        }
+        Cli::Tower(sub) => {
+            let random = new_random(sub.new.seed);
+            let options = sub.options(&Default::default())?;
+            let (row, column) = (sub.new.row, sub.new.column);
+            ConsoleTower::new_with_options(sub.floors, row, column, random, options)?.run()
+        }
+        Cli::Grid(sub) => {
+            let random = new_random(sub.seed);
+            let (row, column) = (sub.row, sub.column);
+            match sub.shape {
+                GridShape::Hex => {
+                    ConsoleGrid::<Hex, _>::new_with_random(row, column, random, "e w ne nw se sw")?
+                        .run()
+                }
+                GridShape::Triangle => {
+                    ConsoleGrid::<Triangle, _>::new_with_random(row, column, random, "e w n s")?
+                        .run()
+                }
+            }
         }
         _ => {
-            println!("new game is only allow new command");
-            return Ok(());
+            println!("new game is only allow new, tower or grid command");
+            Ok(())
         }
-    }?;
-    game.run()
+    }
 }
 
 fn main() {
-    match run() {
-        Err(e) => println!("{}", e.to_string()),
-        _ => {}
+    if let Err(e) = run() {
+        println!("{}", e);
     }
 }
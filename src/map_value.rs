@@ -1,15 +1,47 @@
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 pub enum MapValue {
+    #[default]
     Empty,
     Wall,
     Road,
+    // 地形，可以通过但走进去的代价更高
+    Grass,
+    Mud,
+    Water,
+    // 横竖两条通道在此交叉，只能直行穿过
+    Crossing,
     Border,
+    // 多层迷宫中连接上下两层的楼梯
+    StairsUp,
+    StairsDown,
     St,
     Ed,
 }
 
-impl Default for MapValue {
-    fn default() -> Self {
-        Self::Empty
+impl MapValue {
+    pub fn is_passable(&self) -> bool {
+        matches!(
+            self,
+            Self::Road
+                | Self::Grass
+                | Self::Mud
+                | Self::Water
+                | Self::Crossing
+                | Self::StairsUp
+                | Self::StairsDown
+                | Self::St
+                | Self::Ed
+        )
+    }
+
+    // 走进这个位置的代价，不能通过的位置为 0
+    pub fn cost(&self) -> usize {
+        match self {
+            Self::Grass => 2,
+            Self::Mud => 3,
+            Self::Water => 5,
+            _ if self.is_passable() => 1,
+            _ => 0,
+        }
     }
 }
@@ -0,0 +1,289 @@
+use std::{collections::VecDeque, path::Path};
+
+use rand::Rng;
+
+use crate::{
+    disjoint_set::DisjointSet,
+    errors::{MazeError, MazeResult},
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::Point,
+    random::randarray,
+};
+
+// 标记哪些位置可以开辟道路，以地图坐标为单位，大小不一致时按最近邻缩放到地图大小
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Mask {
+    cells: Grid<bool>,
+}
+
+impl Mask {
+    pub fn from_bools(cells: Vec<Vec<bool>>) -> MazeResult<Self> {
+        let column = cells.iter().map(|line| line.len()).max().unwrap_or(0);
+        if column == 0 {
+            return Err(MazeError::Init(String::from("遮罩不能为空")));
+        }
+        let rows = cells
+            .into_iter()
+            .map(|mut line| {
+                line.resize(column, false);
+                line
+            })
+            .collect();
+        // 每行已经补齐到相同长度
+        let cells = Grid::from_rows(rows).unwrap();
+        Ok(Self { cells })
+    }
+
+    // 空格、`.` 和 `0` 表示遮罩外，其余字符表示可以开辟
+    pub fn from_ascii(text: &str) -> MazeResult<Self> {
+        let cells = text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| !matches!(c, ' ' | '.' | '0'))
+                    .collect()
+            })
+            .collect();
+        Self::from_bools(cells)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> MazeResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::from_ascii(&text),
+            Err(e) => Err(MazeError::Init(e.to_string())),
+        }
+    }
+
+    pub fn row(&self) -> usize {
+        self.cells.row()
+    }
+
+    pub fn column(&self) -> usize {
+        self.cells.column()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.get(p).copied().unwrap_or(false)
+    }
+
+    pub fn resize(&self, row: usize, column: usize) -> Self {
+        if row == self.row() && column == self.column() {
+            return self.clone();
+        }
+        let mut cells = Grid::new(row, column, false);
+        for (i, line) in cells.rows_mut().enumerate() {
+            for (j, cell) in line.iter_mut().enumerate() {
+                *cell = self.cells[i * self.row() / row][j * self.column() / column];
+            }
+        }
+        Self { cells }
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 越界或者在遮罩外
+    pub fn is_outside(&self, p: Point) -> bool {
+        self.is_overrange(p) || matches!(self[p], MapValue::Empty | MapValue::Border)
+    }
+
+    fn apply_mask(&mut self) -> bool {
+        let mask = match &self.options.mask {
+            Some(mask) => mask.resize(self.row(), self.column()),
+            None => return false,
+        };
+        for i in 0..self.row() {
+            for j in 0..self.column() {
+                if !mask.contains(Point(i as i32, j as i32)) {
+                    self.set(Point(i as i32, j as i32), MapValue::Empty);
+                }
+            }
+        }
+        true
+    }
+
+    pub(crate) fn init_mask(&mut self) -> MazeResult<()> {
+        if !self.apply_mask() {
+            return Ok(());
+        }
+        let first = (0..self.row() as i32)
+            .flat_map(|i| (0..self.column() as i32).map(move |j| Point(i, j)))
+            .find(|&p| self[p] != MapValue::Empty);
+        match first {
+            Some(p) => {
+                if self.is_outside(self.inst_st) {
+                    self.inst_st = p;
+                }
+                Ok(())
+            }
+            None => Err(MazeError::Init(String::from("遮罩内没有可以开辟的位置"))),
+        }
+    }
+
+    // 生成器不一定理解遮罩：恢复遮罩外的位置，再把被遮罩切开的区域重新连通
+    pub(crate) fn finish_mask(&mut self) {
+        if !self.apply_mask() {
+            return;
+        }
+        self.connect_regions();
+        // 紧挨着遮罩内的位置画成边框，显示出形状的轮廓
+        let mut outline = vec![];
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                let p = Point(i, j);
+                if self[p] != MapValue::Empty {
+                    continue;
+                }
+                let near = (-1..=1)
+                    .flat_map(|di| (-1..=1).map(move |dj| p + (di, dj)))
+                    .any(|q| !self.is_outside(q));
+                if near {
+                    outline.push(p);
+                }
+            }
+        }
+        for p in outline {
+            self.set(p, MapValue::Border);
+        }
+    }
+
+    fn label_regions(&self) -> (Grid<Option<usize>>, Vec<usize>) {
+        let mut label = Grid::new(self.row(), self.column(), None);
+        let mut sizes = vec![];
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                let start = Point(i, j);
+                if !self[start].is_passable() || label[start].is_some() {
+                    continue;
+                }
+                let id = sizes.len();
+                let mut size = 0;
+                let mut queue = VecDeque::from([start]);
+                label[start] = Some(id);
+                while let Some(p) = queue.pop_front() {
+                    size += 1;
+                    for q in self.neighbours(p) {
+                        if !self[q].is_passable() || label[q].is_some() {
+                            continue;
+                        }
+                        label[q] = Some(id);
+                        queue.push_back(q);
+                    }
+                }
+                sizes.push(size);
+            }
+        }
+        (label, sizes)
+    }
+
+    pub fn connect_regions(&mut self) {
+        let (label, sizes) = self.label_regions();
+        if sizes.len() <= 1 {
+            return;
+        }
+        let mut bridges = vec![];
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                let p = Point(i, j);
+                if self[p] != MapValue::Wall {
+                    continue;
+                }
+                for (a, b) in [(p - (1, 0), p + (1, 0)), (p - (0, 1), p + (0, 1))] {
+                    if self.is_overrange(a) || self.is_overrange(b) {
+                        continue;
+                    }
+                    if let (Some(la), Some(lb)) = (label[a], label[b]) {
+                        if la != lb {
+                            bridges.push((p, la, lb));
+                        }
+                    }
+                }
+            }
+        }
+        let bridges = randarray(&mut self.random, &bridges);
+        let mut sets = DisjointSet::new(sizes.len());
+        for (p, la, lb) in bridges {
+            if sets.union(la, lb) {
+                self.set(p, MapValue::Road);
+            }
+        }
+        // 仍然无法连通的小区域填回墙，只保留最大的区域
+        let mut total = vec![0; sizes.len()];
+        for (id, size) in sizes.iter().enumerate() {
+            total[sets.find(id)] += size;
+        }
+        let largest = (0..sizes.len()).max_by_key(|&id| total[id]).unwrap();
+        for (p, id) in label.points().zip(label.iter()) {
+            if let Some(id) = *id {
+                if !sets.same(id, largest) {
+                    self.set(p, MapValue::Wall);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions};
+
+    use super::*;
+
+    const HEART: &str = "
+  #####     #####
+ #######   #######
+#########.#########
+###################
+###################
+ #################
+  ###############
+   #############
+     #########
+       #####
+         #
+";
+
+    #[test]
+    fn test_mask() {
+        let mask = Mask::from_ascii(HEART).unwrap();
+        let generators = [
+            "backtracker",
+            "prim",
+            "kruskal",
+            "hunt-and-kill",
+            "binary-tree",
+            "sidewinder",
+            "growing-tree:random",
+            "wilson",
+            "aldous-broder",
+            "eller",
+            "division",
+            "dungeon:min=1,max=2",
+        ];
+        for name in generators {
+            let options = MazeOptions {
+                generator: name.parse::<Generator>().unwrap(),
+                mask: Some(mask.clone()),
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(5);
+            let maze = MazeMap::new_with_options(23, 39, random, options).unwrap();
+            let scaled = mask.resize(23, 39);
+            for i in 0..23 {
+                for j in 0..39 {
+                    let p = Point(i, j);
+                    assert_eq!(scaled.contains(p), !maze.is_outside(p), "{}", name);
+                }
+            }
+            assert_ne!(maze.st, maze.ed, "{}", name);
+            let path = maze.solve(maze.st).unwrap();
+            assert!(path.iter().all(|&p| scaled.contains(p)), "{}", name);
+        }
+    }
+}
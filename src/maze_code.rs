@@ -0,0 +1,377 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    maze_options::{MazeOptions, PIPELINE_VERSION},
+};
+
+// 代码格式的版本，编码方式改变时提高
+// 版本 2 增加了生成之后的步骤的版本，版本 1 的代码按第 1 版的步骤生成
+pub const CODE_VERSION: u8 = 2;
+
+// Crockford base32，不含 I L O U，解码时不区分大小写
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+// 重新生成迷宫需要的全部信息
+// 同一个代码只有在使用相同的随机数生成器时才能得到相同的迷宫，控制台使用 `ChaCha8Rng`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MazeCode {
+    pub generator_version: u8,
+    pub row: usize,
+    pub column: usize,
+    pub seed: u64,
+    pub options: MazeOptions,
+}
+
+impl MazeCode {
+    pub fn new(row: usize, column: usize, seed: u64, options: MazeOptions) -> MazeResult<Self> {
+        // 遮罩来自文件，无法放进代码
+        if options.mask.is_some() {
+            return Err(MazeError::Code(String::from("mask can not be encoded")));
+        }
+        Ok(Self {
+            generator_version: options.generator.version(),
+            row,
+            column,
+            seed,
+            options,
+        })
+    }
+
+    fn options_text(&self) -> String {
+        let options = &self.options;
+        let mut text = format!(
+            "{};{};{};{};{}",
+            options.generator, options.loops, options.wrap, options.placement, options.frame as u8
+        );
+        // 没有地形时省略，之前的代码仍然有效
+        if options.terrain != 0 {
+            text += &format!(";{}", options.terrain);
+        }
+        text
+    }
+
+    fn parse_options(s: &str) -> Result<MazeOptions, String> {
+        let items: Vec<&str> = s.split(';').collect();
+        let (items, terrain) = match items.as_slice() {
+            [items @ .., terrain] if items.len() == 5 => (items, *terrain),
+            items => (items, "0"),
+        };
+        let &[generator, loops, wrap, placement, frame] = items else {
+            return Err(format!("options: {}", s));
+        };
+        Ok(MazeOptions {
+            generator: generator.parse()?,
+            loops: loops.parse().map_err(|_| format!("loops: {}", loops))?,
+            mask: None,
+            wrap: wrap.parse()?,
+            placement: placement.parse()?,
+            frame: match frame {
+                "0" => false,
+                "1" => true,
+                _ => return Err(format!("frame: {}", frame)),
+            },
+            terrain: terrain
+                .parse()
+                .map_err(|_| format!("terrain: {}", terrain))?,
+            pipeline: PIPELINE_VERSION,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![CODE_VERSION, self.generator_version, self.options.pipeline];
+        write_varint(&mut bytes, self.row as u64);
+        write_varint(&mut bytes, self.column as u64);
+        bytes.extend(self.seed.to_le_bytes());
+        bytes.extend(self.options_text().into_bytes());
+        let checksum = checksum(&bytes);
+        bytes.extend(checksum.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 4 {
+            return Err(String::from("too short"));
+        }
+        let (bytes, tail) = bytes.split_at(bytes.len() - 2);
+        if checksum(bytes).to_le_bytes() != tail {
+            return Err(String::from("checksum mismatch"));
+        }
+        let (pipeline, mut pos) = match bytes[0] {
+            1 => (1, 2),
+            2 => (*bytes.get(2).ok_or_else(|| String::from("too short"))?, 3),
+            version => return Err(format!("unsupported code version {}", version)),
+        };
+        if !(1..=PIPELINE_VERSION).contains(&pipeline) {
+            return Err(format!("unsupported pipeline version {}", pipeline));
+        }
+        let generator_version = bytes[1];
+        let row = read_varint(bytes, &mut pos)?;
+        let column = read_varint(bytes, &mut pos)?;
+        let seed = bytes
+            .get(pos..pos + 8)
+            .ok_or_else(|| String::from("missing seed"))?;
+        let seed = u64::from_le_bytes(seed.try_into().unwrap());
+        let options = std::str::from_utf8(&bytes[pos + 8..]).map_err(|e| e.to_string())?;
+        let options = MazeOptions {
+            pipeline,
+            ..Self::parse_options(options)?
+        };
+        if options.generator.version() != generator_version {
+            return Err(format!(
+                "unsupported version {} of generator {}",
+                generator_version, options.generator
+            ));
+        }
+        let size = |v: u64| usize::try_from(v).map_err(|_| format!("size: {}", v));
+        Ok(Self {
+            generator_version,
+            row: size(row)?,
+            column: size(column)?,
+            seed,
+            options,
+        })
+    }
+}
+
+impl Display for MazeCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", encode_base32(&self.to_bytes()))
+    }
+}
+
+impl FromStr for MazeCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(&decode_base32(s)?)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or_else(|| String::from("unexpected end"))?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(String::from("varint too long"))
+}
+
+// FNV-1a，取低 16 位
+fn checksum(bytes: &[u8]) -> u16 {
+    let mut hash: u32 = 0x811c9dc5;
+    for &byte in bytes {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    (hash ^ (hash >> 16)) as u16
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut res = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            res.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        res.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    res
+}
+
+// 忽略 `-` 和空白，容易看错的 O、I、L 按 0 和 1 处理
+fn decode_base32(s: &str) -> Result<Vec<u8>, String> {
+    let mut res = vec![];
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in s.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or_else(|| format!("invalid character {}", c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            res.push((buffer >> bits) as u8);
+        }
+    }
+    // 最后一个字符中不足一个字节的部分必须为 0，否则改动它不会被校验和发现
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return Err(String::from("invalid padding"));
+    }
+    Ok(res)
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng + SeedableRng,
+{
+    pub fn new_with_seed(
+        row: usize,
+        column: usize,
+        seed: u64,
+        options: MazeOptions,
+    ) -> MazeResult<Self> {
+        let random = Random::seed_from_u64(seed);
+        let mut maze = Self::new_with_options(row, column, random, options)?;
+        maze.seed = Some(seed);
+        Ok(maze)
+    }
+
+    pub fn generate_with_seed(&mut self, row: usize, column: usize, seed: u64) -> MazeResult<()> {
+        self.generate_with_new_random(row, column, Random::seed_from_u64(seed))?;
+        self.seed = Some(seed);
+        Ok(())
+    }
+
+    pub fn from_code(code: &str) -> MazeResult<Self> {
+        let code: MazeCode = code.parse().map_err(MazeError::Code)?;
+        Self::new_with_seed(code.row, code.column, code.seed, code.options)
+    }
+
+    // 只有知道种子的迷宫才能编码
+    pub fn to_code(&self) -> MazeResult<String> {
+        let seed = match self.seed {
+            Some(seed) => seed,
+            None => return Err(MazeError::Code(String::from("seed of the maze is unknown"))),
+        };
+        let code = MazeCode::new(self.row(), self.column(), seed, self.options.clone())?;
+        Ok(code.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+
+    use crate::{
+        generator::Generator, map_value::MapValue, placement::Placement, point::Point, wrap::Wrap,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_maze_code() {
+        let options = MazeOptions {
+            generator: "division:rooms=20".parse().unwrap(),
+            loops: 30,
+            wrap: Wrap::Cylinder,
+            placement: Placement::Fixed(Point(0, 0), Point(10, 4)),
+            ..Default::default()
+        };
+        let code = MazeCode::new(15, 20, u64::MAX - 3, options).unwrap();
+        let text = code.to_string();
+        assert!(text.chars().all(|c| ALPHABET.contains(&(c as u8))));
+        assert_eq!(text.parse(), Ok(code.clone()));
+        assert_eq!(text.to_lowercase().replace('0', "o").parse(), Ok(code));
+
+        // 改动任意一个字符都会被校验和发现
+        for i in 0..text.len() {
+            let mut bytes = text.clone().into_bytes();
+            bytes[i] = if bytes[i] == b'2' { b'3' } else { b'2' };
+            let changed = String::from_utf8(bytes).unwrap();
+            assert!(changed.parse::<MazeCode>().is_err(), "{}", changed);
+        }
+
+        for generator in [Generator::Kruskal, "weave:density=80".parse().unwrap()] {
+            let options = MazeOptions {
+                frame: generator == Generator::Kruskal,
+                terrain: 20,
+                ..MazeOptions::new(generator)
+            };
+            let maze = MazeMap::<StdRng>::new_with_seed(13, 21, 42, options).unwrap();
+            let code = maze.to_code().unwrap();
+            let again = MazeMap::<StdRng>::from_code(&code).unwrap();
+            assert_eq!(
+                (again.map(), again.st, again.ed),
+                (maze.map(), maze.st, maze.ed)
+            );
+        }
+
+        let maze = MazeMap::new_with_random(5, 5, StdRng::seed_from_u64(1)).unwrap();
+        assert!(maze.to_code().is_err());
+        assert!(MazeMap::<StdRng>::from_code("not a code").is_err());
+    }
+
+    // 固定的代码必须一直生成同样的迷宫，生成结果改变时要提高版本并保留旧的行为
+    #[test]
+    fn test_pinned_codes() {
+        let codes = [
+            (
+                "040GE2R10000000000064RB3DDT74RB3DDJQ4ESN60XPWVVECMXP6VVJDSJQ4ESGSCT0",
+                1,
+                [
+                    "S#.#.......",
+                    ".....##.#.#",
+                    "#.###...#..",
+                    "##...#.#..#",
+                    "#..#..#..#.",
+                    "..#.#...##.",
+                    ".#....#...E",
+                ],
+            ),
+            (
+                "080G41RB0400000000000RK1CDNQ8WK1CDNPAWHV6MR3PVKFDSJKPRVFE9Q6AWHV627VT",
+                2,
+                [
+                    "S..#.......",
+                    ".....##.#..",
+                    "#.###...#..",
+                    "##.....#..#",
+                    "#..#..#..#.",
+                    "....#...##.",
+                    "......#...E",
+                ],
+            ),
+        ];
+        for (code, pipeline, expected) in codes {
+            let maze = MazeMap::<StdRng>::from_code(code).unwrap();
+            assert_eq!(maze.options.pipeline, pipeline);
+            let lines: Vec<String> = maze
+                .map()
+                .rows()
+                .map(|line| {
+                    line.iter()
+                        .map(|value| match value {
+                            MapValue::Wall => '#',
+                            MapValue::St => 'S',
+                            MapValue::Ed => 'E',
+                            _ => '.',
+                        })
+                        .collect()
+                })
+                .collect();
+            assert_eq!(lines, expected, "{}", code);
+            // 重新编码后仍然按原来的版本生成
+            let again = MazeMap::<StdRng>::from_code(&maze.to_code().unwrap()).unwrap();
+            assert_eq!(again.map(), maze.map());
+        }
+    }
+}
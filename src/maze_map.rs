@@ -1,29 +1,39 @@
-use std::{
-    collections::VecDeque,
-    ops::{Index, IndexMut},
-};
+use std::ops::{Index, IndexMut};
 
 use rand::{rngs::ThreadRng, Rng};
 
 use crate::{
     errors::{MazeError, MazeResult},
+    events::GenerationRecord,
+    generator::{Generator, MazeGenerator},
+    grid::Grid,
     map_value::MapValue,
+    maze_options::MazeOptions,
     point::{CanPointIndex, Point},
-    random::randarray,
+    rect::Rect,
+    solver::{check_ends, Bfs, SolutionCache},
 };
 
-type TMap = Vec<Vec<MapValue>>;
+type TMap = Grid<MapValue>;
 
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct MazeMap<Random = ThreadRng>
 where
     Random: Rng,
 {
     pub random: Random,
-    pub map: TMap,
+    // 只能通过会清空求解缓存的方法修改
+    map: TMap,
     pub inst_st: Point,
     pub st: Point,
     pub ed: Point,
+    pub options: MazeOptions,
+    pub rooms: Vec<Rect>,
+    // 生成这个迷宫的种子，不知道时为 None
+    pub seed: Option<u64>,
+    // 不为 None 时记录生成过程中的事件
+    pub record: Option<GenerationRecord>,
+    solution: SolutionCache,
 }
 
 impl<Random> MazeMap<Random>
@@ -31,206 +41,199 @@ where
     Random: Rng,
 {
     pub fn row(&self) -> usize {
-        self.map.len()
+        self.map.row()
     }
 
     pub fn column(&self) -> usize {
-        self.map[0].len()
+        self.map.column()
     }
 
-    pub fn is_overrange(&self, p: Point) -> bool {
-        let (i, j) = (p.0, p.1);
-        if i < 0 || j < 0 {
-            return true;
-        }
-        if i as usize >= self.row() || j as usize >= self.column() {
-            return true;
-        }
-        return false;
+    pub fn rooms(&self) -> &[Rect] {
+        &self.rooms
     }
 
-    fn init_get_walls(&self, p: Point, lp: Point) -> Vec<Point> {
-        let mut result = vec![];
-        for point in p.get_range_vec() {
-            if self.is_overrange(point) {
-                continue;
-            }
-            if point == lp {
-                continue;
-            }
-            result.push(point);
-        }
-        result
+    pub fn map(&self) -> &TMap {
+        &self.map
     }
 
-    fn init_check_walls(&self, p: Point, lp: Point) -> bool {
-        let temp = self.init_get_walls(p, lp);
-        if temp.is_empty() {
-            return false;
-        }
-        for t in temp {
-            if self.map[t] != MapValue::Wall {
-                return false;
-            }
-        }
-        true
+    // 修改地图前清空缓存的解
+    pub fn map_mut(&mut self) -> &mut TMap {
+        self.solution.clear();
+        &mut self.map
     }
 
-    fn init_map(&mut self) -> MazeResult<()> {
-        let mut stack = VecDeque::new();
-        let mut p = self.inst_st;
-        let mut lp = Point(-1, -1);
-        let mut step = 0;
-        stack.push_back((p, lp, step));
-        while !stack.is_empty() {
-            (p, lp, step) = stack.pop_back().unwrap();
-            if !self.init_check_walls(p, lp) {
-                continue;
-            }
-            self.map[p] = MapValue::Road;
-            let mut around_walls = self.init_get_walls(p, lp);
-            if around_walls.is_empty() {
-                continue;
-            }
-            around_walls = randarray(&mut self.random, &around_walls);
-            for wall in around_walls {
-                stack.push_back((wall, p, step + 1));
-            }
-        }
+    pub fn set_map(&mut self, map: TMap) {
+        self.solution.clear();
+        self.map = map;
+    }
 
-        // 是否获取了 st 和 ed
-        let mut st_get = false;
-        let mut ed_get = false;
-        for i in 0..self.row() {
-            for j in 0..self.column() {
-                if st_get && ed_get {
-                    return Ok(());
-                }
-                if !st_get && self.map[Point(i, j)] == MapValue::Road {
-                    self.st = Point(i as i32, j as i32);
-                    self.map[self.st] = MapValue::St;
-                    st_get = true;
-                }
-                let ed_idx = Point(self.row() - 1 - i, self.column() - 1 - j);
-                if !ed_get && self.map[ed_idx] == MapValue::Road {
-                    self.ed = Point(ed_idx.0 as i32, ed_idx.1 as i32);
-                    self.map[self.ed] = MapValue::Ed;
-                    ed_get = true;
-                }
-            }
-        }
-        Ok(())
+    pub fn into_map(self) -> TMap {
+        self.map
+    }
+
+    pub fn is_overrange(&self, p: Point) -> bool {
+        self.map.is_overrange(p)
     }
 
-    pub fn new_with_random(row: i32, column: i32, random: Random) -> MazeResult<Self> {
+    // 坐标用 i32 表示，行列数不能超过 i32 的范围
+    fn check_size(row: usize, column: usize) -> MazeResult<()> {
         if row < 2 || column < 2 {
             return Err(MazeError::Init(String::from("行和列不能小于2")));
         }
+        if row > i32::MAX as usize || column > i32::MAX as usize {
+            return Err(MazeError::Init(String::from("行和列超出范围")));
+        }
+        Ok(())
+    }
+
+    fn init_map_by<G>(&mut self, generator: &G) -> MazeResult<()>
+    where
+        G: MazeGenerator<Random>,
+    {
+        self.solution.clear();
+        self.init_frame()?;
+        self.check_wrap()?;
+        self.init_mask()?;
+        self.begin_record();
+        generator.generate(self)?;
+        self.finish_mask();
+        self.braid(self.options.loops);
+
+        self.place_st_ed()?;
+        self.paint_terrain(self.options.terrain);
+        self.finish_frame()
+    }
+
+    pub fn new_with_random(row: usize, column: usize, random: Random) -> MazeResult<Self> {
+        Self::new_with_options(row, column, random, MazeOptions::default())
+    }
+
+    pub fn new_with_generator(
+        row: usize,
+        column: usize,
+        random: Random,
+        generator: Generator,
+    ) -> MazeResult<Self> {
+        Self::new_with_options(row, column, random, MazeOptions::new(generator))
+    }
+
+    pub fn new_with_options(
+        row: usize,
+        column: usize,
+        random: Random,
+        options: MazeOptions,
+    ) -> MazeResult<Self> {
+        let generator = options.generator;
+        Self::new_by(row, column, random, options, &generator)
+    }
+
+    // 用 generator 代替 `options.generator` 生成，其余选项照常生效
+    pub fn new_by<G>(
+        row: usize,
+        column: usize,
+        random: Random,
+        options: MazeOptions,
+        generator: &G,
+    ) -> MazeResult<Self>
+    where
+        G: MazeGenerator<Random>,
+    {
+        Self::check_size(row, column)?;
         let mut self_ = Self {
             random,
-            map: vec![vec![MapValue::Wall; column as usize]; row as usize],
+            map: Grid::new(row, column, MapValue::Wall),
             inst_st: Default::default(),
             st: Default::default(),
             ed: Default::default(),
+            options,
+            rooms: vec![],
+            seed: None,
+            record: None,
+            solution: SolutionCache::default(),
         };
-        self_.init_map()?;
+        self_.init_map_by(generator)?;
         Ok(self_)
     }
 
     pub fn generate_with_new_random(
         &mut self,
-        row: i32,
-        column: i32,
+        row: usize,
+        column: usize,
         random: Random,
     ) -> MazeResult<()> {
         self.random = random;
-        self.map = vec![vec![MapValue::Wall; column as usize]; row as usize];
-        self.inst_st = Default::default();
-        self.st = Default::default();
-        self.ed = Default::default();
-        self.init_map()
+        self.generate(row, column)
+    }
+
+    pub fn generate(&mut self, row: usize, column: usize) -> MazeResult<()> {
+        let generator = self.options.generator;
+        self.generate_by(row, column, &generator)
     }
 
-    pub fn generate(&mut self, row: i32, column: i32) -> MazeResult<()> {
-        self.map = vec![vec![MapValue::Wall; column as usize]; row as usize];
+    pub fn generate_by<G>(&mut self, row: usize, column: usize, generator: &G) -> MazeResult<()>
+    where
+        G: MazeGenerator<Random>,
+    {
+        Self::check_size(row, column)?;
+        self.map = Grid::new(row, column, MapValue::Wall);
         self.inst_st = Default::default();
         self.st = Default::default();
         self.ed = Default::default();
-        self.init_map()
+        self.rooms.clear();
+        self.seed = None;
+        self.init_map_by(generator)
     }
 
     pub fn re_generate(&mut self) -> MazeResult<()> {
-        self.generate(self.row() as i32, self.column() as i32)
+        self.generate(self.row(), self.column())
     }
 
-    fn solve_get_roads(&self, map_temp: &Vec<Vec<Option<Point>>>, p: Point) -> Vec<Point> {
-        p.get_range_vec()
-            .iter()
-            .filter(|&p2| {
-                if self.is_overrange(*p2) {
-                    return false;
-                }
-                if let Some(_) = map_temp[*p2] {
-                    return false;
-                }
-                if self.map[*p2] == MapValue::Wall {
-                    return false;
-                }
-                true
-            })
-            .map(|p2| *p2)
-            .collect()
+    // 到终点的路径由缓存的搜索树得到，只有第一次需要搜索整个地图
+    pub fn solve(&self, pos: Point) -> MazeResult<Vec<Point>> {
+        check_ends(self, pos, self.ed)?;
+        self.solution.path(self, pos)
     }
 
-    pub fn solve(&self, pos: Point) -> MazeResult<Vec<Point>> {
-        if pos == self.ed {
-            return Ok(vec![pos]);
-        }
-        let mut queue = VecDeque::new();
-        let mut map_temp = vec![vec![None; self.column()]; self.row()];
-        let mut p = pos;
-        let mut lp = None;
-        let mut step = 0;
-        queue.push_back((p, lp, step));
-        while p != self.ed {
-            if queue.is_empty() {
-                return Err(MazeError::QueueEmpty);
-            }
-            (p, lp, step) = queue.pop_front().unwrap();
-            map_temp[p] = lp;
-            let roads = self.solve_get_roads(&map_temp, p);
-            for road in roads {
-                queue.push_back((road, Some(p), step + 1));
-            }
-        }
-        let mut rp = map_temp[self.ed].unwrap();
-        let mut res = vec![Point::default(); step + 1];
-        res[step] = self.ed;
-        for i in (0..step).rev() {
-            res[i] = rp;
-            rp = map_temp[rp].unwrap();
-        }
-        if res[0] != pos {
-            return Err(MazeError::SolveException);
+    pub fn is_solution_cached(&self) -> bool {
+        self.solution.is_cached()
+    }
+
+    // 从 a 到 b 的最短路径，包括两端
+    pub fn solve_between(&self, a: Point, b: Point) -> MazeResult<Vec<Point>> {
+        check_ends(self, a, b)?;
+        if a == b {
+            return Ok(vec![a]);
         }
-        Ok(res)
+        Ok(Bfs::search(self, a, b, |_| true, false)?.path)
     }
 }
 
+// 缓存的解不影响迷宫是否相等
+impl<Random> PartialEq for MazeMap<Random>
+where
+    Random: Rng + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.random == other.random
+            && self.map == other.map
+            && self.inst_st == other.inst_st
+            && self.st == other.st
+            && self.ed == other.ed
+            && self.options == other.options
+            && self.rooms == other.rooms
+            && self.seed == other.seed
+            && self.record == other.record
+    }
+}
+
+impl<Random> Eq for MazeMap<Random> where Random: Rng + Eq {}
+
 impl<Random> MazeMap<Random>
 where
     Random: Rng + Default,
 {
-    pub fn new(row: i32, column: i32) -> MazeResult<Self> {
-        if row < 2 || column < 2 {
-            return Err(MazeError::Init(String::from("行和列不能小于2")));
-        }
-        let mut self_ = Self {
-            map: vec![vec![MapValue::Wall; column as usize]; row as usize],
-            ..Default::default()
-        };
-        self_.init_map()?;
-        Ok(self_)
+    pub fn new(row: usize, column: usize) -> MazeResult<Self> {
+        Self::new_with_random(row, column, Random::default())
     }
 }
 
@@ -252,6 +255,7 @@ where
     T: CanPointIndex,
 {
     fn index_mut(&mut self, index: Point<T>) -> &mut Self::Output {
+        self.solution.clear();
         &mut self.map[index]
     }
 }
@@ -0,0 +1,45 @@
+use crate::{generator::Generator, mask::Mask, placement::Placement, wrap::Wrap};
+
+// 生成之后的步骤（打通死路、放置起点终点、地形、边框）的版本
+// 这些步骤的结果改变时提高，并保留旧版本的行为，让旧的迷宫代码继续生成相同的迷宫
+pub const PIPELINE_VERSION: u8 = 2;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MazeOptions {
+    pub generator: Generator,
+    // 生成后打通死路的百分比，0 为完美迷宫，100 为完全编织
+    pub loops: u8,
+    pub mask: Option<Mask>,
+    pub wrap: Wrap,
+    pub placement: Placement,
+    // 外围加一圈边框，起点和终点是边框上的开口
+    pub frame: bool,
+    // 生成后铺上草地、泥地或水的道路格子的百分比
+    pub terrain: u8,
+    // 按哪个版本执行生成之后的步骤，新的迷宫总是用 `PIPELINE_VERSION`
+    pub pipeline: u8,
+}
+
+impl Default for MazeOptions {
+    fn default() -> Self {
+        Self {
+            generator: Generator::default(),
+            loops: 0,
+            mask: None,
+            wrap: Wrap::default(),
+            placement: Placement::default(),
+            frame: false,
+            terrain: 0,
+            pipeline: PIPELINE_VERSION,
+        }
+    }
+}
+
+impl MazeOptions {
+    pub fn new(generator: Generator) -> Self {
+        Self {
+            generator,
+            ..Default::default()
+        }
+    }
+}
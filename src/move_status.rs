@@ -1,11 +1,16 @@
+use std::fmt::Display;
+
 use crate::point::Point;
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum MoveStatus {
     Up,
     Down,
     Left,
     Right,
+    // 沿楼梯上下楼，只在多层迷宫中有效
+    Upstairs,
+    Downstairs,
 }
 
 impl MoveStatus {
@@ -15,6 +20,24 @@ impl MoveStatus {
             Self::Down => p + (1, 0),
             Self::Left => p - (0, 1),
             Self::Right => p + (0, 1),
+            Self::Upstairs | Self::Downstairs => p,
+        }
+    }
+
+    pub fn is_stairs(&self) -> bool {
+        matches!(self, Self::Upstairs | Self::Downstairs)
+    }
+}
+
+impl Display for MoveStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Up => write!(f, "up"),
+            Self::Down => write!(f, "down"),
+            Self::Left => write!(f, "left"),
+            Self::Right => write!(f, "right"),
+            Self::Upstairs => write!(f, "upstairs"),
+            Self::Downstairs => write!(f, "downstairs"),
         }
     }
 }
@@ -0,0 +1,282 @@
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    events::GenerationEvent,
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::Point,
+};
+
+// 生成后放置起点和终点的方式
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Placement {
+    // 按行优先的顺序第一个和最后一个道路，遮罩或边框使角上没有道路时不一定离角最近
+    #[default]
+    Corner,
+    Random,
+    // 起点随机开在边缘上，终点开在离起点最远的边缘上
+    Border,
+    // 指定起点和终点，必须是道路
+    // 加边框时是边框内的坐标，即去掉边框后的地图上的位置
+    Fixed(Point, Point),
+    // 两次 BFS 找到相距最远的两个位置，完美迷宫中即为最长的解
+    Farthest,
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Corner => write!(f, "corner"),
+            Self::Random => write!(f, "random"),
+            Self::Border => write!(f, "border"),
+            Self::Fixed(st, ed) => write!(f, "fixed:{},{},{},{}", st.0, st.1, ed.0, ed.1),
+            Self::Farthest => write!(f, "farthest"),
+        }
+    }
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let placement = match s.to_ascii_lowercase().as_str() {
+            "corner" => Self::Corner,
+            "random" => Self::Random,
+            "border" => Self::Border,
+            "farthest" | "max" => Self::Farthest,
+            other => {
+                let values: Option<Vec<i32>> = other
+                    .strip_prefix("fixed:")
+                    .map(|v| v.split(',').map(|n| n.trim().parse().ok()).collect())
+                    .unwrap_or(None);
+                match values.as_deref() {
+                    Some(&[a, b, c, d]) => Self::Fixed(Point(a, b), Point(c, d)),
+                    _ => return Err(format!("unknown placement: {}", s)),
+                }
+            }
+        };
+        Ok(placement)
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 按行优先的顺序逐个产生，不需要把全部道路放进数组
+    fn placement_roads(&self) -> impl Iterator<Item = Point> + '_ {
+        self.map()
+            .points()
+            .filter(|&p| self[p] == MapValue::Road && self.is_placeable(p))
+    }
+
+    // 从 from 出发 BFS，能到达的道路中离 from 最远的一个，filter 用来限制候选位置
+    // 距离相同时取后到达的，交叉点按普通路口计算，编织迷宫中的距离只是近似值
+    fn placement_farthest(&self, from: Point, filter: impl Fn(Point) -> bool) -> Option<Point> {
+        let mut seen = Grid::new(self.row(), self.column(), false);
+        let mut queue = VecDeque::from([(from, 0)]);
+        let mut res: Option<(Point, usize)> = None;
+        seen[from] = true;
+        while let Some((p, step)) = queue.pop_front() {
+            if p != from
+                && self[p] == MapValue::Road
+                && filter(p)
+                && self.is_placeable(p)
+                && res.is_none_or(|(_, best)| step >= best)
+            {
+                res = Some((p, step));
+            }
+            for p2 in self.neighbours(p) {
+                if self[p2].is_passable() && !seen[p2] {
+                    seen[p2] = true;
+                    queue.push_back((p2, step + 1));
+                }
+            }
+        }
+        res.map(|(p, _)| p)
+    }
+
+    fn is_on_border(&self, p: Point) -> bool {
+        p.get_range_vec().into_iter().any(|p2| self.is_outside(p2))
+    }
+
+    // 行优先顺序中的第一个和最后一个，不计算到角的距离
+    fn place_corner(&mut self) -> Option<(Point, Point)> {
+        let mut roads = self.placement_roads();
+        let first = roads.next()?;
+        Some((first, roads.last().unwrap_or(first)))
+    }
+
+    fn place_random(&mut self) -> Option<(Point, Point)> {
+        // 先数出道路的数量，再按下标取出，避免保存全部道路
+        let count = self.placement_roads().count();
+        if count < 2 {
+            return None;
+        }
+        let st = self.random.gen_range(0..count);
+        let mut ed = self.random.gen_range(0..count - 1);
+        if ed == st {
+            ed = count - 1;
+        }
+        let st = self.placement_roads().nth(st)?;
+        let ed = self.placement_roads().nth(ed)?;
+        Some((st, ed))
+    }
+
+    fn place_border(&mut self) -> Option<(Point, Point)> {
+        let border: Vec<Point> = self
+            .placement_roads()
+            .filter(|&p| self.is_on_border(p))
+            .collect();
+        if border.is_empty() {
+            return None;
+        }
+        let st = border[self.random.gen_range(0..border.len())];
+        let ed = self.placement_farthest(st, |p| self.is_on_border(p))?;
+        Some((st, ed))
+    }
+
+    fn place_farthest(&mut self) -> Option<(Point, Point)> {
+        let start = self.placement_roads().next()?;
+        let st = self.placement_farthest(start, |_| true).unwrap_or(start);
+        let ed = self.placement_farthest(st, |_| true)?;
+        Some((st, ed))
+    }
+
+    pub(crate) fn place_st_ed(&mut self) -> MazeResult<()> {
+        let res = match self.options.placement {
+            Placement::Corner => self.place_corner(),
+            Placement::Random => self.place_random(),
+            Placement::Border => self.place_border(),
+            Placement::Fixed(st, ed) => {
+                let is_road = |p: Point| {
+                    !self.is_overrange(p) && self[p] == MapValue::Road && self.is_placeable(p)
+                };
+                if st == ed || !is_road(st) || !is_road(ed) {
+                    return Err(MazeError::Init(format!(
+                        "{} 和 {} 必须是不同的道路",
+                        st, ed
+                    )));
+                }
+                Some((st, ed))
+            }
+            Placement::Farthest => self.place_farthest(),
+        };
+        // 加边框时终点的通道和起点的相邻，改用离起点最远的可以一起打通的位置
+        let res = match res {
+            Some((st, ed)) if !self.is_placeable_pair(st, ed) => {
+                if let Placement::Fixed(..) = self.options.placement {
+                    return Err(MazeError::Init(format!(
+                        "{} 和 {} 无法一起打通到边框",
+                        st, ed
+                    )));
+                }
+                self.placement_farthest(st, |p| self.is_placeable_pair(st, p))
+                    .map(|ed| (st, ed))
+            }
+            res => res,
+        };
+        let (st, ed) = match res {
+            Some(res) => res,
+            None => return Err(MazeError::Init(String::from("找不到放置起点和终点的位置"))),
+        };
+        self.st = st;
+        self.ed = ed;
+        self[st] = MapValue::St;
+        self[ed] = MapValue::Ed;
+        self.emit(GenerationEvent::Start(st));
+        self.emit(GenerationEvent::End(ed));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions};
+
+    use super::*;
+
+    fn new_maze(placement: Placement, seed: u64) -> MazeMap<StdRng> {
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            placement,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(seed);
+        MazeMap::new_with_options(15, 21, random, options).unwrap()
+    }
+
+    #[test]
+    fn test_placement() {
+        for seed in 0..5 {
+            let corner = new_maze(Placement::Corner, seed);
+            assert_eq!((corner.st, corner.ed), (Point(0, 0), Point(14, 20)));
+
+            // 最远的两点之间的路径不会比其他放置方式短
+            let farthest = new_maze(Placement::Farthest, seed);
+            let longest = farthest.solve(farthest.st).unwrap().len();
+            let passable = |maze: &MazeMap<StdRng>| -> Vec<bool> {
+                maze.map().iter().map(|v| v.is_passable()).collect()
+            };
+            for placement in [Placement::Corner, Placement::Random, Placement::Border] {
+                let maze = new_maze(placement, seed);
+                assert_eq!(passable(&maze), passable(&farthest));
+                assert!(
+                    maze.solve(maze.st).unwrap().len() <= longest,
+                    "{}",
+                    placement
+                );
+            }
+
+            let border = new_maze(Placement::Border, seed);
+            assert!(border.is_on_border(border.st) && border.is_on_border(border.ed));
+        }
+
+        let fixed = Placement::Fixed(Point(2, 4), Point(10, 6));
+        let maze = new_maze(fixed, 1);
+        assert_eq!((maze.st, maze.ed), (Point(2, 4), Point(10, 6)));
+        // 格子型迷宫中奇数行奇数列总是墙
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            placement: Placement::Fixed(Point(1, 1), Point(10, 6)),
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(1);
+        assert!(MazeMap::new_with_options(15, 21, random, options).is_err());
+
+        // 加边框时指定的是边框内的坐标
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            placement: Placement::Fixed(Point(0, 0), Point(10, 18)),
+            frame: true,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(1);
+        let maze = MazeMap::new_with_options(13, 21, random, options).unwrap();
+        let inner = maze.export(false).unwrap();
+        assert_eq!(
+            (inner[Point(0, 0)], inner[Point(10, 18)]),
+            (MapValue::St, MapValue::Ed)
+        );
+    }
+
+    #[test]
+    fn test_parse_placement() {
+        for placement in [
+            Placement::Corner,
+            Placement::Random,
+            Placement::Border,
+            Placement::Fixed(Point(0, 2), Point(8, 10)),
+            Placement::Farthest,
+        ] {
+            assert_eq!(placement.to_string().parse(), Ok(placement));
+        }
+        assert!("fixed:1,2,3".parse::<Placement>().is_err());
+    }
+}
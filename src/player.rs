@@ -6,6 +6,10 @@ pub struct Player {
     pub name: String,
     pub step: i32,
     pub move_times: i32,
+    // 使用提示时所在的位置
+    pub hints: Vec<Point>,
+    // 使用提示等扣的分，计入分数
+    pub penalty: i32,
 }
 
 impl Player {
@@ -15,6 +19,18 @@ impl Player {
             name: name.into(),
             step: 0,
             move_times: 0,
+            hints: vec![],
+            penalty: 0,
         }
     }
+
+    pub fn record_hint(&mut self, penalty: i32) {
+        self.hints.push(self.pos);
+        self.penalty += penalty;
+    }
+
+    // 分数越低越好，为步数加上罚分
+    pub fn score(&self) -> i32 {
+        self.step + self.penalty
+    }
 }
@@ -24,6 +24,9 @@ where
     }
 }
 
+// 上下左右四个方向，与 `get_range_vec` 的顺序一致
+pub const OFFSETS: [Point; 4] = [Point(-1, 0), Point(1, 0), Point(0, -1), Point(0, 1)];
+
 pub trait CanPointIndex {
     fn one() -> Self;
     fn to_usize(&self) -> usize;
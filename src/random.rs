@@ -34,14 +34,28 @@ where
     res
 }
 
-pub fn randarray<T>(rng: &mut impl Rng, arr: &Vec<T>) -> Vec<T>
+// 原地打乱，交换的顺序与 `randindex` 相同，同一个随机数生成器得到相同的排列
+pub fn shuffle<T>(rng: &mut impl Rng, arr: &mut [T]) {
+    for i in 0..arr.len() {
+        let temp = rng.gen_range(i..arr.len());
+        arr.swap(i, temp);
+    }
+}
+
+pub fn randarray<T>(rng: &mut impl Rng, arr: &[T]) -> Vec<T>
 where
-    T: Default + Clone,
+    T: Clone,
 {
-    let res_index: Vec<usize> = randindex::<usize>(rng, arr.len());
-    let mut res = vec![T::default(); arr.len()];
-    for (i, index) in res_index.iter().enumerate() {
-        res[i] = arr[*index].clone();
-    }
+    let mut res = arr.to_vec();
+    shuffle(rng, &mut res);
     res
 }
+
+// 从主种子派生出第 index 个子种子，结果只取决于两个参数，不依赖随机数生成器的实现
+pub fn derive_seed(master: u64, index: u64) -> u64 {
+    // splitmix64
+    let mut z = master.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,53 @@
+use crate::point::Point;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Rect {
+    pub top: i32,
+    pub left: i32,
+    pub height: i32,
+    pub width: i32,
+}
+
+impl Rect {
+    pub fn new(top: i32, left: i32, height: i32, width: i32) -> Self {
+        Self {
+            top,
+            left,
+            height,
+            width,
+        }
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.top + self.height
+    }
+
+    pub fn right(&self) -> i32 {
+        self.left + self.width
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.0 >= self.top && p.0 < self.bottom() && p.1 >= self.left && p.1 < self.right()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.top < other.bottom()
+            && other.top < self.bottom()
+            && self.left < other.right()
+            && other.left < self.right()
+    }
+
+    pub fn center(&self) -> Point {
+        Point(self.top + self.height / 2, self.left + self.width / 2)
+    }
+
+    pub fn points(&self) -> Vec<Point> {
+        let mut res = vec![];
+        for i in self.top..self.bottom() {
+            for j in self.left..self.right() {
+                res.push(Point(i, j));
+            }
+        }
+        res
+    }
+}
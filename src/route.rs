@@ -0,0 +1,168 @@
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::Point,
+};
+
+// 求最优顺序时允许的最多途经点，动态规划的状态数为 2^n * n
+pub const MAX_WAYPOINTS: usize = 12;
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 交叉点上只能直走，不能在交叉点上停下来再转向
+    fn check_waypoints(&self, waypoints: &[Point]) -> MazeResult<()> {
+        for &p in waypoints {
+            if self.is_overrange(p) || !self[p].is_passable() || self[p] == MapValue::Crossing {
+                return Err(MazeError::SolveException);
+            }
+        }
+        Ok(())
+    }
+
+    // 从 a 出发按顺序经过每个途经点后到达 b，每一段都是最短路径
+    pub fn solve_via(&self, a: Point, waypoints: &[Point], b: Point) -> MazeResult<Vec<Point>> {
+        self.check_waypoints(waypoints)?;
+        let mut res = vec![a];
+        let mut from = a;
+        for &to in waypoints.iter().chain([&b]) {
+            let path = self.solve_between(from, to)?;
+            res.extend(path.into_iter().skip(1));
+            from = to;
+        }
+        Ok(res)
+    }
+
+    // 找出经过全部途经点的总长度最短的顺序，返回路径和途经点的下标顺序
+    pub fn solve_via_best(
+        &self,
+        a: Point,
+        waypoints: &[Point],
+        b: Point,
+    ) -> MazeResult<(Vec<Point>, Vec<usize>)> {
+        let n = waypoints.len();
+        if n > MAX_WAYPOINTS {
+            return Err(MazeError::TooManyWaypoints(n));
+        }
+        self.check_waypoints(waypoints)?;
+        // dist[i][j] 为第 i 个途经点到第 j 个途经点的步数，最后一列是到 b 的步数
+        let mut dist = vec![vec![0; n + 1]; n];
+        let mut from_a = vec![0; n + 1];
+        for (i, &p) in waypoints.iter().chain([&a]).enumerate() {
+            let distances = self.distance_map(p)?;
+            let row: Vec<usize> = waypoints
+                .iter()
+                .chain([&b])
+                .map(|&q| distances.distance(q).ok_or(MazeError::SolveException))
+                .collect::<MazeResult<_>>()?;
+            if i == n {
+                from_a = row;
+            } else {
+                dist[i] = row;
+            }
+        }
+
+        // best[mask][i] 为从 a 出发经过 mask 中的点、最后停在 i 的最短长度
+        let full = (1 << n) - 1;
+        let mut best = vec![vec![usize::MAX; n]; 1 << n];
+        let mut last = vec![vec![usize::MAX; n]; 1 << n];
+        for i in 0..n {
+            best[1 << i][i] = from_a[i];
+        }
+        for mask in 1..=full {
+            for i in 0..n {
+                if mask & (1 << i) == 0 || best[mask][i] == usize::MAX {
+                    continue;
+                }
+                for j in 0..n {
+                    if mask & (1 << j) != 0 {
+                        continue;
+                    }
+                    let next = mask | (1 << j);
+                    let length = best[mask][i] + dist[i][j];
+                    if length < best[next][j] {
+                        best[next][j] = length;
+                        last[next][j] = i;
+                    }
+                }
+            }
+        }
+        let end = (0..n).min_by_key(|&i| best[full][i] + dist[i][n]);
+        let mut order = vec![];
+        let (mut mask, mut i) = (full, end);
+        while let Some(k) = i {
+            order.push(k);
+            let prev = last[mask][k];
+            mask ^= 1 << k;
+            i = (prev != usize::MAX).then_some(prev);
+        }
+        order.reverse();
+        let ordered: Vec<Point> = order.iter().map(|&i| waypoints[i]).collect();
+        Ok((self.solve_via(a, &ordered, b)?, order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions};
+
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        let options = MazeOptions {
+            loops: 40,
+            ..MazeOptions::new(Generator::Kruskal)
+        };
+        let random = StdRng::seed_from_u64(6);
+        let maze = MazeMap::new_with_options(21, 31, random, options).unwrap();
+        let a = Point(20, 0);
+        let b = Point(0, 30);
+        let path = maze.solve_between(a, b).unwrap();
+        assert_eq!((path[0], path[path.len() - 1]), (a, b));
+        assert_eq!(maze.solve_between(a, a).unwrap(), vec![a]);
+        assert!(maze.solve_between(a, Point(1, 1)).is_err());
+
+        let waypoints = [Point(0, 0), Point(10, 30), Point(20, 30), Point(10, 10)];
+        let route = maze.solve_via(a, &waypoints, b).unwrap();
+        for w in route.windows(2) {
+            assert!(maze.neighbours(w[0]).contains(&w[1]));
+        }
+        let mut pos = 0;
+        for p in waypoints.iter().chain([&b]) {
+            pos += route[pos..].iter().position(|q| q == p).unwrap();
+        }
+
+        // 与全排列中最短的一条一样长
+        let (best, order) = maze.solve_via_best(a, &waypoints, b).unwrap();
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3]);
+        let ordered: Vec<Point> = order.iter().map(|&i| waypoints[i]).collect();
+        assert_eq!(best, maze.solve_via(a, &ordered, b).unwrap());
+        let shortest = (0..256)
+            .map(|x: usize| [x % 4, x / 4 % 4, x / 16 % 4, x / 64])
+            .filter(|order| (0..4).all(|i| order.contains(&i)))
+            .map(|order| {
+                let ordered = order.map(|i| waypoints[i]);
+                maze.solve_via(a, &ordered, b).unwrap().len()
+            })
+            .min()
+            .unwrap();
+        assert_eq!(best.len(), shortest);
+        assert!(best.len() <= route.len());
+
+        assert_eq!(maze.solve_via_best(a, &[], b).unwrap().0, path);
+        let many = vec![a; MAX_WAYPOINTS + 1];
+        assert_eq!(
+            maze.solve_via_best(a, &many, b),
+            Err(MazeError::TooManyWaypoints(MAX_WAYPOINTS + 1))
+        );
+    }
+}
@@ -0,0 +1,508 @@
+pub mod a_star;
+pub mod bfs;
+pub mod bidirectional;
+pub mod dead_end_filling;
+pub mod dijkstra;
+pub mod tremaux;
+pub mod wall_follower;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::{Point, OFFSETS},
+    wrap::Wrap,
+};
+
+pub use self::{
+    a_star::AStar,
+    bfs::Bfs,
+    bidirectional::Bidirectional,
+    dead_end_filling::DeadEndFilling,
+    dijkstra::Dijkstra,
+    tremaux::Tremaux,
+    wall_follower::{Hand, WallFollower},
+};
+
+// 求解的结果：从起点到终点的路径和搜索时依次访问的位置
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Solution {
+    pub path: Vec<Point>,
+    // 按访问的先后排列，沿原路返回的算法中同一个位置会出现多次
+    pub order: Vec<Point>,
+}
+
+impl Solution {
+    pub fn explored(&self) -> HashSet<Point> {
+        self.order.iter().copied().collect()
+    }
+}
+
+pub trait MazeSolver<Random>
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution>;
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Solver {
+    #[default]
+    Bfs,
+    AStar,
+    Bidirectional,
+    DeadEndFilling,
+    Dijkstra,
+    WallFollower(Hand),
+    Tremaux,
+}
+
+impl<Random> MazeSolver<Random> for Solver
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        match self {
+            Self::Bfs => Bfs.solve(maze, from, to),
+            Self::AStar => AStar.solve(maze, from, to),
+            Self::Bidirectional => Bidirectional.solve(maze, from, to),
+            Self::DeadEndFilling => DeadEndFilling.solve(maze, from, to),
+            Self::Dijkstra => Dijkstra.solve(maze, from, to),
+            Self::WallFollower(hand) => WallFollower::new(*hand).solve(maze, from, to),
+            Self::Tremaux => Tremaux.solve(maze, from, to),
+        }
+    }
+}
+
+impl Display for Solver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bfs => write!(f, "bfs"),
+            Self::AStar => write!(f, "astar"),
+            Self::Bidirectional => write!(f, "bidirectional"),
+            Self::DeadEndFilling => write!(f, "dead-end-filling"),
+            Self::Dijkstra => write!(f, "dijkstra"),
+            Self::WallFollower(hand) => write!(f, "wall-follower:{}", hand),
+            Self::Tremaux => write!(f, "tremaux"),
+        }
+    }
+}
+
+impl FromStr for Solver {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s, None),
+        };
+        let solver = match name.to_ascii_lowercase().as_str() {
+            "bfs" => Self::Bfs,
+            "astar" | "a*" => Self::AStar,
+            "bidirectional" => Self::Bidirectional,
+            "dead-end-filling" => Self::DeadEndFilling,
+            "dijkstra" | "cheapest" => Self::Dijkstra,
+            "wall-follower" => {
+                let hand = match arg {
+                    Some(arg) => arg.parse()?,
+                    None => Hand::default(),
+                };
+                return Ok(Self::WallFollower(hand));
+            }
+            "tremaux" | "trémaux" => Self::Tremaux,
+            _ => return Err(format!("unknown solver: {}", name)),
+        };
+        match arg {
+            Some(arg) => Err(format!("solver {} takes no argument: {}", name, arg)),
+            None => Ok(solver),
+        }
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    pub fn solve_by<S>(&self, solver: &S, from: Point, to: Point) -> MazeResult<Solution>
+    where
+        S: MazeSolver<Random>,
+    {
+        solver.solve(self, from, to)
+    }
+}
+
+// 以下为求解器共用的工具
+// 到达一个位置时走的方向用 `OFFSETS` 中的下标表示，起点没有方向，用 ROOT 表示
+
+pub(crate) const ROOT: usize = OFFSETS.len();
+
+pub(crate) fn check_ends<Random>(maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<()>
+where
+    Random: Rng,
+{
+    for p in [from, to] {
+        if maze.is_overrange(p) || !maze[p].is_passable() {
+            return Err(MazeError::SolveException);
+        }
+    }
+    Ok(())
+}
+
+// 交叉点横竖可以各经过一次，看作两个位置，用经过的方向是否为竖直区分
+pub(crate) fn crossing_axis<Random>(maze: &MazeMap<Random>, p: Point, dir: usize) -> Option<bool>
+where
+    Random: Rng,
+{
+    (dir < ROOT && maze[p] == MapValue::Crossing).then(|| OFFSETS[dir].0 != 0)
+}
+
+// 从 p 出发可以走的方向和到达的位置，dir 为到达 p 时走的方向，在交叉点上只能直走
+pub(crate) fn moves<Random>(
+    maze: &MazeMap<Random>,
+    p: Point,
+    dir: usize,
+) -> impl Iterator<Item = (usize, Point)> + '_
+where
+    Random: Rng,
+{
+    let dirs = match crossing_axis(maze, p, dir) {
+        Some(_) => dir..dir + 1,
+        None => 0..ROOT,
+    };
+    dirs.filter_map(move |d| Some((d, maze.step(p, OFFSETS[d])?)))
+        .filter(|&(_, q)| maze[q].is_passable())
+}
+
+// 按位置保存的值，交叉点按横竖两个方向分别保存
+#[derive(Debug, Clone)]
+pub(crate) struct NodeMap<T> {
+    cells: Grid<T>,
+    crossings: HashMap<(Point, bool), T>,
+    empty: T,
+}
+
+impl<T> NodeMap<T>
+where
+    T: Clone,
+{
+    pub(crate) fn new<Random>(maze: &MazeMap<Random>, empty: T) -> Self
+    where
+        Random: Rng,
+    {
+        Self {
+            cells: Grid::new(maze.row(), maze.column(), empty.clone()),
+            crossings: HashMap::new(),
+            empty,
+        }
+    }
+
+    pub(crate) fn get<Random>(&self, maze: &MazeMap<Random>, p: Point, dir: usize) -> &T
+    where
+        Random: Rng,
+    {
+        match crossing_axis(maze, p, dir) {
+            Some(axis) => self.crossings.get(&(p, axis)).unwrap_or(&self.empty),
+            None => &self.cells[p],
+        }
+    }
+
+    // 在交叉点上以 ROOT 保存时两个方向都保存
+    pub(crate) fn set<Random>(&mut self, maze: &MazeMap<Random>, p: Point, dir: usize, value: T)
+    where
+        Random: Rng,
+    {
+        match crossing_axis(maze, p, dir) {
+            Some(axis) => {
+                self.crossings.insert((p, axis), value);
+            }
+            None => {
+                if maze[p] == MapValue::Crossing {
+                    self.crossings.insert((p, true), value.clone());
+                    self.crossings.insert((p, false), value.clone());
+                }
+                self.cells[p] = value;
+            }
+        }
+    }
+}
+
+// 搜索树，每个位置只保存到达时走的方向，0 表示还没有到达
+#[derive(Debug, Clone)]
+pub(crate) struct SearchTree(NodeMap<u8>);
+
+impl SearchTree {
+    pub(crate) fn new<Random>(maze: &MazeMap<Random>) -> Self
+    where
+        Random: Rng,
+    {
+        Self(NodeMap::new(maze, 0))
+    }
+
+    pub(crate) fn contains<Random>(&self, maze: &MazeMap<Random>, p: Point, dir: usize) -> bool
+    where
+        Random: Rng,
+    {
+        *self.0.get(maze, p, dir) != 0
+    }
+
+    pub(crate) fn insert<Random>(&mut self, maze: &MazeMap<Random>, p: Point, dir: usize)
+    where
+        Random: Rng,
+    {
+        self.0.set(maze, p, dir, dir as u8 + 1);
+    }
+
+    // 从根到 p 的路径，dir 为到达 p 时走的方向
+    pub(crate) fn path<Random>(
+        &self,
+        maze: &MazeMap<Random>,
+        p: Point,
+        dir: usize,
+    ) -> MazeResult<Vec<Point>>
+    where
+        Random: Rng,
+    {
+        let arrival = |p: Point, dir: usize| match *self.0.get(maze, p, dir) {
+            0 => Err(MazeError::SolveException),
+            d => Ok(d as usize - 1),
+        };
+        let mut res = vec![p];
+        let (mut p, mut dir) = (p, arrival(p, dir)?);
+        while dir != ROOT {
+            p = match maze.step(p, Point(0, 0) - OFFSETS[dir]) {
+                Some(p) => p,
+                None => return Err(MazeError::SolveException),
+            };
+            // 经过交叉点时沿原来的方向
+            dir = arrival(p, dir)?;
+            res.push(p);
+        }
+        res.reverse();
+        Ok(res)
+    }
+}
+
+// 以终点为根的搜索树，第一次求解时生成，之后到终点的路径只需沿树走回根
+// 地图只能通过会清空缓存的方法修改：重新生成、`IndexMut`、`map_mut` 和 `set_map`
+// 终点或者环绕方式改变后按新的终点和相邻关系重新生成
+#[derive(Debug, Default)]
+pub(crate) struct SolutionCache(RwLock<Option<(Point, Wrap, Arc<SearchTree>)>>);
+
+impl SolutionCache {
+    pub(crate) fn clear(&mut self) {
+        *self.0.get_mut().unwrap() = None;
+    }
+
+    pub(crate) fn is_cached(&self) -> bool {
+        self.0.read().unwrap().is_some()
+    }
+
+    fn tree<Random>(&self, maze: &MazeMap<Random>) -> Arc<SearchTree>
+    where
+        Random: Rng,
+    {
+        let wrap = maze.options.wrap;
+        if let Some((root, cached_wrap, tree)) = self.0.read().unwrap().as_ref() {
+            if *root == maze.ed && *cached_wrap == wrap {
+                return tree.clone();
+            }
+        }
+        let tree = Arc::new(Bfs::tree(maze, maze.ed));
+        *self.0.write().unwrap() = Some((maze.ed, wrap, tree.clone()));
+        tree
+    }
+
+    pub(crate) fn path<Random>(&self, maze: &MazeMap<Random>, from: Point) -> MazeResult<Vec<Point>>
+    where
+        Random: Rng,
+    {
+        let tree = self.tree(maze);
+        // 交叉点上取横竖两个方向中较短的一条
+        let dirs = if maze[from] == MapValue::Crossing {
+            vec![0, 2]
+        } else {
+            vec![ROOT]
+        };
+        let mut path = dirs
+            .into_iter()
+            .filter_map(|dir| tree.path(maze, from, dir).ok())
+            .min_by_key(|path| path.len())
+            .ok_or(MazeError::QueueEmpty)?;
+        path.reverse();
+        Ok(path)
+    }
+}
+
+impl Clone for SolutionCache {
+    fn clone(&self) -> Self {
+        Self(RwLock::new(self.0.read().unwrap().clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, maze_options::MazeOptions, wrap::Wrap};
+
+    use super::*;
+
+    fn all_solvers() -> Vec<Solver> {
+        vec![
+            Solver::Bfs,
+            Solver::AStar,
+            Solver::Bidirectional,
+            Solver::DeadEndFilling,
+            Solver::Dijkstra,
+            Solver::WallFollower(Hand::Left),
+            Solver::WallFollower(Hand::Right),
+            Solver::Tremaux,
+        ]
+    }
+
+    // 每一步都是允许的移动，经过交叉点时直走
+    fn check_path(maze: &MazeMap<StdRng>, path: &[Point], from: Point, to: Point) {
+        assert_eq!((path[0], *path.last().unwrap()), (from, to));
+        let mut dir = ROOT;
+        for w in path.windows(2) {
+            let next = moves(maze, w[0], dir).find(|&(_, q)| q == w[1]);
+            dir = next.expect("invalid step").0;
+        }
+    }
+
+    #[test]
+    fn test_solvers() {
+        // 最后一项表示是否为完美迷宫
+        let mazes = [
+            ("backtracker", 0, Wrap::None, true),
+            ("kruskal", 0, Wrap::Cylinder, true),
+            ("prim", 40, Wrap::None, false),
+            ("weave", 0, Wrap::None, true),
+            ("weave:density=80", 30, Wrap::None, false),
+            ("dungeon", 0, Wrap::None, false),
+        ];
+        for (generator, loops, wrap, perfect) in mazes {
+            for seed in 0..3 {
+                let options = MazeOptions {
+                    generator: generator.parse::<Generator>().unwrap(),
+                    loops,
+                    wrap,
+                    ..Default::default()
+                };
+                let random = StdRng::seed_from_u64(seed);
+                let maze = MazeMap::new_with_options(21, 30, random, options).unwrap();
+                let shortest = maze.solve(maze.st).unwrap().len();
+                for solver in all_solvers() {
+                    let solution = match maze.solve_by(&solver, maze.st, maze.ed) {
+                        Ok(solution) => solution,
+                        // 有回路时沿墙走可能一直绕圈
+                        Err(_) if !perfect => {
+                            assert!(matches!(solver, Solver::WallFollower(_)));
+                            continue;
+                        }
+                        Err(e) => panic!("{} {} {}", generator, solver, e),
+                    };
+                    check_path(&maze, &solution.path, maze.st, maze.ed);
+                    let explored = solution.explored();
+                    assert!(
+                        solution.path.iter().all(|p| explored.contains(p)),
+                        "{} {}",
+                        generator,
+                        solver
+                    );
+                    if matches!(
+                        solver,
+                        Solver::Bfs
+                            | Solver::AStar
+                            | Solver::Bidirectional
+                            | Solver::DeadEndFilling
+                            | Solver::Dijkstra
+                    ) {
+                        assert_eq!(solution.path.len(), shortest, "{} {}", generator, solver);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solution_cache() {
+        for generator in ["kruskal", "weave:density=80"] {
+            let options = MazeOptions {
+                generator: generator.parse().unwrap(),
+                loops: 30,
+                ..Default::default()
+            };
+            let random = StdRng::seed_from_u64(2);
+            let mut maze = MazeMap::new_with_options(21, 31, random, options).unwrap();
+            assert!(!maze.is_solution_cached());
+            // 缓存不影响是否相等
+            let fresh = maze.clone();
+            let roads: Vec<Point> = maze
+                .map()
+                .points()
+                .filter(|&p| maze[p].is_passable())
+                .collect();
+            for &p in roads.iter() {
+                let path = maze.solve(p).unwrap();
+                let shortest = Bfs.solve(&maze, p, maze.ed).unwrap().path;
+                assert_eq!(path.len(), shortest.len(), "{} {}", generator, p);
+                check_path(&maze, &path, p, maze.ed);
+            }
+            assert!(maze.is_solution_cached());
+            assert_eq!(maze, fresh);
+
+            // 改变终点后按新的终点重新生成
+            let ed = maze.ed;
+            maze.ed = maze.st;
+            assert_eq!(maze.solve(maze.st).unwrap(), vec![maze.st]);
+            let back = maze.solve(ed).unwrap();
+            check_path(&maze, &back, ed, maze.st);
+            assert_eq!(
+                back.len(),
+                Bfs.solve(&maze, ed, maze.st).unwrap().path.len()
+            );
+            maze.ed = ed;
+            assert_eq!(maze.solve(maze.st).unwrap().len(), back.len());
+
+            // 改变环绕方式后相邻关系不同，也要重新生成
+            maze.options.wrap = Wrap::Cylinder;
+            let path = maze.solve(maze.st).unwrap();
+            let shortest = Bfs.solve(&maze, maze.st, maze.ed).unwrap().path;
+            assert_eq!(path.len(), shortest.len(), "{}", generator);
+            check_path(&maze, &path, maze.st, maze.ed);
+            maze.options.wrap = Wrap::None;
+
+            // 修改地图或重新生成后缓存失效
+            let p = roads[roads.len() / 2];
+            maze[p] = MapValue::Wall;
+            assert!(!maze.is_solution_cached());
+            maze.solve(maze.st).unwrap();
+            maze.map_mut()[p] = MapValue::Road;
+            assert!(!maze.is_solution_cached());
+            maze.solve(maze.st).unwrap();
+            maze.re_generate().unwrap();
+            assert!(!maze.is_solution_cached());
+        }
+    }
+
+    #[test]
+    fn test_solver_parse() {
+        for solver in [Solver::AStar, Solver::WallFollower(Hand::Right)] {
+            assert_eq!(solver.to_string().parse(), Ok(solver));
+        }
+        assert_eq!("a*".parse(), Ok(Solver::AStar));
+        assert!("bfs:left".parse::<Solver>().is_err());
+        assert!("wall-follower:up".parse::<Solver>().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, SearchTree, Solution, ROOT};
+
+// 以曼哈顿距离为估价的 A*，环绕时取绕过接缝的较短距离，见 `MazeMap::wrapped_distance`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct AStar;
+
+impl<Random> MazeSolver<Random> for AStar
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        let mut tree = SearchTree::new(maze);
+        let mut order = vec![];
+        // 估价相同时先走离起点远的，再按入队顺序
+        let mut heap = BinaryHeap::new();
+        let mut count = 0;
+        heap.push((
+            Reverse(maze.wrapped_distance(from, to)),
+            0,
+            Reverse(count),
+            (from.0, from.1),
+            ROOT,
+        ));
+        // 估价函数是一致的，第一次出队时的距离就是最短距离
+        while let Some((_, step, _, (i, j), dir)) = heap.pop() {
+            let p = Point(i, j);
+            if tree.contains(maze, p, dir) {
+                continue;
+            }
+            tree.insert(maze, p, dir);
+            order.push(p);
+            if p == to {
+                let path = tree.path(maze, p, dir)?;
+                return Ok(Solution { path, order });
+            }
+            for (d, q) in moves(maze, p, dir) {
+                if tree.contains(maze, q, d) {
+                    continue;
+                }
+                count += 1;
+                let cost = step + 1 + maze.wrapped_distance(q, to);
+                heap.push((Reverse(cost), step + 1, Reverse(count), (q.0, q.1), d));
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, SearchTree, Solution, ROOT};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Bfs;
+
+impl Bfs {
+    // 只经过 allow 为 true 的位置，record 为 false 时不记录访问顺序以节省内存
+    pub(crate) fn search<Random>(
+        maze: &MazeMap<Random>,
+        from: Point,
+        to: Point,
+        allow: impl Fn(Point) -> bool,
+        record: bool,
+    ) -> MazeResult<Solution>
+    where
+        Random: Rng,
+    {
+        let mut tree = SearchTree::new(maze);
+        let mut order = vec![];
+        let mut queue = VecDeque::from([(from, ROOT)]);
+        // 入队时就记录父节点，有回路时也能保证最短
+        tree.insert(maze, from, ROOT);
+        while let Some((p, dir)) = queue.pop_front() {
+            if record {
+                order.push(p);
+            }
+            if p == to {
+                let path = tree.path(maze, p, dir)?;
+                return Ok(Solution { path, order });
+            }
+            for (d, q) in moves(maze, p, dir) {
+                if allow(q) && !tree.contains(maze, q, d) {
+                    tree.insert(maze, q, d);
+                    queue.push_back((q, d));
+                }
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+
+    // 从 root 出发能到达的全部位置组成的搜索树
+    pub(crate) fn tree<Random>(maze: &MazeMap<Random>, root: Point) -> SearchTree
+    where
+        Random: Rng,
+    {
+        let mut tree = SearchTree::new(maze);
+        let mut queue = VecDeque::from([(root, ROOT)]);
+        tree.insert(maze, root, ROOT);
+        while let Some((p, dir)) = queue.pop_front() {
+            for (d, q) in moves(maze, p, dir) {
+                if !tree.contains(maze, q, d) {
+                    tree.insert(maze, q, d);
+                    queue.push_back((q, d));
+                }
+            }
+        }
+        tree
+    }
+}
+
+impl<Random> MazeSolver<Random> for Bfs
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        Self::search(maze, from, to, |_| true, true)
+    }
+}
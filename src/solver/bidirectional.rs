@@ -0,0 +1,71 @@
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, SearchTree, Solution, ROOT};
+
+// 从起点和终点同时广度优先搜索，每次把较小的一侧扩展一整层，两侧相遇时结束
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Bidirectional;
+
+impl<Random> MazeSolver<Random> for Bidirectional
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        if from == to {
+            return Ok(Solution {
+                path: vec![from],
+                order: vec![from],
+            });
+        }
+        let mut trees = [SearchTree::new(maze), SearchTree::new(maze)];
+        trees[0].insert(maze, from, ROOT);
+        trees[1].insert(maze, to, ROOT);
+        let mut frontiers = [vec![(from, ROOT)], vec![(to, ROOT)]];
+        // 按加入搜索树的先后记录，两侧各自的搜索树都在其中
+        let mut order = vec![from, to];
+        loop {
+            let side = if frontiers[0].len() <= frontiers[1].len() {
+                0
+            } else {
+                1
+            };
+            let mut next = vec![];
+            let mut best: Option<Vec<Point>> = None;
+            for (p, dir) in std::mem::take(&mut frontiers[side]) {
+                for (d, q) in moves(maze, p, dir) {
+                    if trees[side].contains(maze, q, d) {
+                        continue;
+                    }
+                    trees[side].insert(maze, q, d);
+                    order.push(q);
+                    next.push((q, d));
+                    // 另一侧沿相反方向经过 q，交叉点上是同一个方向
+                    if !trees[1 - side].contains(maze, q, d) {
+                        continue;
+                    }
+                    let mut path = trees[0].path(maze, q, d)?;
+                    let back = trees[1].path(maze, q, d)?;
+                    path.extend(back.into_iter().rev().skip(1));
+                    if best.as_ref().is_none_or(|best| path.len() < best.len()) {
+                        best = Some(path);
+                    }
+                }
+            }
+            // 这一层中相遇的路径里最短的一条就是最短路径
+            if let Some(path) = best {
+                return Ok(Solution { path, order });
+            }
+            if next.is_empty() {
+                return Err(MazeError::QueueEmpty);
+            }
+            frontiers[side] = next;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+
+use rand::Rng;
+
+use crate::{errors::MazeResult, grid::Grid, map_value::MapValue, maze_map::MazeMap, point::Point};
+
+use super::{bfs::Bfs, check_ends, moves, MazeSolver, Solution, ROOT};
+
+// 不断填掉死路，完美迷宫中剩下的就是答案，有回路时在剩下的位置中再找最短路径
+// 交叉点不会被填掉
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct DeadEndFilling;
+
+impl<Random> MazeSolver<Random> for DeadEndFilling
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        let can_fill = |p: Point| p != from && p != to && maze[p] != MapValue::Crossing;
+        let mut degree = Grid::new(maze.row(), maze.column(), 0);
+        let mut filled = Grid::new(maze.row(), maze.column(), false);
+        let mut queue = VecDeque::new();
+        for p in maze.map().points() {
+            if !maze[p].is_passable() {
+                continue;
+            }
+            degree[p] = moves(maze, p, ROOT).count();
+            if degree[p] <= 1 && can_fill(p) {
+                filled[p] = true;
+                queue.push_back(p);
+            }
+        }
+        let mut order = vec![];
+        while let Some(p) = queue.pop_front() {
+            order.push(p);
+            for (_, q) in moves(maze, p, ROOT) {
+                if filled[q] || !can_fill(q) {
+                    continue;
+                }
+                degree[q] -= 1;
+                if degree[q] <= 1 {
+                    filled[q] = true;
+                    queue.push_back(q);
+                }
+            }
+        }
+        let solution = Bfs::search(maze, from, to, |p| !filled[p], true)?;
+        order.extend(solution.order);
+        Ok(Solution {
+            path: solution.path,
+            order,
+        })
+    }
+}
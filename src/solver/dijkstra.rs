@@ -0,0 +1,54 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, NodeMap, SearchTree, Solution, ROOT};
+
+// 按地形代价求代价最小的路径，走进一个位置的代价见 `MapValue::cost`
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Dijkstra;
+
+impl<Random> MazeSolver<Random> for Dijkstra
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        let mut tree = SearchTree::new(maze);
+        let mut best = NodeMap::new(maze, usize::MAX);
+        let mut order = vec![];
+        // 代价相同时按入队顺序
+        let mut heap = BinaryHeap::new();
+        let mut count = 0;
+        heap.push((Reverse(0), Reverse(count), (from.0, from.1), ROOT));
+        best.set(maze, from, ROOT, 0);
+        while let Some((Reverse(cost), _, (i, j), dir)) = heap.pop() {
+            let p = Point(i, j);
+            if tree.contains(maze, p, dir) {
+                continue;
+            }
+            tree.insert(maze, p, dir);
+            order.push(p);
+            if p == to {
+                let path = tree.path(maze, p, dir)?;
+                return Ok(Solution { path, order });
+            }
+            for (d, q) in moves(maze, p, dir) {
+                let next = cost + maze[q].cost();
+                if tree.contains(maze, q, d) || next >= *best.get(maze, q, d) {
+                    continue;
+                }
+                best.set(maze, q, d, next);
+                count += 1;
+                heap.push((Reverse(next), Reverse(count), (q.0, q.1), d));
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+}
@@ -0,0 +1,51 @@
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, SearchTree, Solution, ROOT};
+
+// Trémaux 算法：走过的通道做标记，遇到走过的位置就原路返回，走投无路时退回上一个路口
+// 访问顺序包括返回时经过的位置，栈中剩下的就是路径
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Tremaux;
+
+impl<Random> MazeSolver<Random> for Tremaux
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        let mut seen = SearchTree::new(maze);
+        seen.insert(maze, from, ROOT);
+        let mut order = vec![from];
+        // 位置、到达时的方向和下一个要尝试的方向
+        let mut stack = vec![(from, ROOT, 0)];
+        while let Some(&mut (p, dir, ref mut next)) = stack.last_mut() {
+            if p == to {
+                let path = stack.iter().map(|&(p, _, _)| p).collect();
+                return Ok(Solution { path, order });
+            }
+            let found =
+                moves(maze, p, dir).find(|&(d, q)| d >= *next && !seen.contains(maze, q, d));
+            match found {
+                Some((d, q)) => {
+                    *next = d + 1;
+                    seen.insert(maze, q, d);
+                    order.push(q);
+                    stack.push((q, d, 0));
+                }
+                None => {
+                    stack.pop();
+                    if let Some(&(back, _, _)) = stack.last() {
+                        order.push(back);
+                    }
+                }
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+}
@@ -0,0 +1,109 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+use super::{check_ends, moves, MazeSolver, NodeMap, Solution, ROOT};
+
+// 沿着哪一侧的墙走
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Hand {
+    #[default]
+    Left,
+    Right,
+}
+
+impl Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Left => write!(f, "left"),
+            Self::Right => write!(f, "right"),
+        }
+    }
+}
+
+impl FromStr for Hand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            _ => Err(format!("unknown hand: {}", s)),
+        }
+    }
+}
+
+// 顺时针排列的方向：上、右、下、左
+const CLOCKWISE: [usize; 4] = [0, 3, 1, 2];
+
+// 一只手始终扶着墙走，访问顺序就是走过的每一步，去掉走回头路的部分就是路径
+// 终点不在起点所在的那面墙上时会一直绕圈，这时返回错误
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct WallFollower {
+    pub hand: Hand,
+}
+
+impl WallFollower {
+    pub fn new(hand: Hand) -> Self {
+        Self { hand }
+    }
+}
+
+impl<Random> MazeSolver<Random> for WallFollower
+where
+    Random: Rng,
+{
+    fn solve(&self, maze: &MazeMap<Random>, from: Point, to: Point) -> MazeResult<Solution> {
+        check_ends(maze, from, to)?;
+        // 相对当前朝向顺时针转动的次数，按优先顺序排列
+        let turns = match self.hand {
+            Hand::Left => [3, 0, 1, 2],
+            Hand::Right => [1, 0, 3, 2],
+        };
+        // 每个位置按到达时的朝向记录，同样的状态再次出现说明在绕圈
+        let mut seen = NodeMap::new(maze, 0u8);
+        // 位置在路径中的下标加 1
+        let mut index = NodeMap::new(maze, 0);
+        let mut path = vec![(from, ROOT)];
+        index.set(maze, from, ROOT, 1);
+        let mut order = vec![from];
+        let (mut p, mut dir, mut heading) = (from, ROOT, 0);
+        while p != to {
+            let allowed: Vec<(usize, Point)> = moves(maze, p, dir).collect();
+            let c = CLOCKWISE.iter().position(|&d| d == heading).unwrap_or(0);
+            let next = turns
+                .iter()
+                .map(|t| CLOCKWISE[(c + t) % CLOCKWISE.len()])
+                .find_map(|d| allowed.iter().find(|&&(d2, _)| d2 == d).copied());
+            let (d, q) = match next {
+                Some(next) => next,
+                None => return Err(MazeError::SolveException),
+            };
+            let mark = *seen.get(maze, q, d);
+            if mark & (1 << d) != 0 {
+                return Err(MazeError::SolveException);
+            }
+            seen.set(maze, q, d, mark | (1 << d));
+            // 回到路径上已有的位置时去掉中间绕的部分
+            let k = *index.get(maze, q, d);
+            if k != 0 {
+                for (r, rd) in path.drain(k..) {
+                    index.set(maze, r, rd, 0);
+                }
+            } else {
+                path.push((q, d));
+                index.set(maze, q, d, path.len());
+            }
+            order.push(q);
+            (p, dir, heading) = (q, d, d);
+        }
+        let path = path.into_iter().map(|(p, _)| p).collect();
+        Ok(Solution { path, order })
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::Display;
+
+use rand::Rng;
+
+use crate::{
+    errors::MazeResult,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    point::{Point, OFFSETS},
+};
+
+// 迷宫的结构指标，用来比较不同关卡和生成器的难度
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MazeStats {
+    // 从起点到终点的步数
+    pub solution_length: usize,
+    // 全部可以通过的位置
+    pub cells: usize,
+    pub dead_ends: usize,
+    // 三条及以上通路的路口
+    pub junctions: usize,
+    // 路口处多出来的分支数，三岔路口为 1，十字路口为 2
+    pub branches: usize,
+    // 两个路口或死路之间的平均通道长度
+    pub corridor_length: f64,
+    // 死路到最近路口的平均长度，越大说明死路越少越长，迷宫越像河流
+    pub river: f64,
+    // 解上转弯的次数
+    pub turns: usize,
+    // 解经过的路口数，即需要做选择的次数
+    pub decisions: usize,
+    // 解上的位置占全部道路的比例
+    pub solution_fraction: f64,
+    // 解的长度与起点终点之间曼哈顿距离的比值
+    pub tortuosity: f64,
+}
+
+impl Display for MazeStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = [
+            format!("solution length: {}", self.solution_length),
+            format!("cells: {}", self.cells),
+            format!("dead ends: {}", self.dead_ends),
+            format!("junctions: {}", self.junctions),
+            format!("branches: {}", self.branches),
+            format!("corridor length: {:.2}", self.corridor_length),
+            format!("river: {:.2}", self.river),
+            format!("turns: {}", self.turns),
+            format!("decisions: {}", self.decisions),
+            format!("solution fraction: {:.2}", self.solution_fraction),
+            format!("tortuosity: {:.2}", self.tortuosity),
+        ];
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl MazeStats {
+    // 死路占全部位置的比例
+    pub fn dead_end_ratio(&self) -> f64 {
+        if self.cells == 0 {
+            return 0.0;
+        }
+        self.dead_ends as f64 / self.cells as f64
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 相邻的道路和对应的方向，交叉点只算一个普通的通道
+    fn stats_roads(&self, p: Point) -> Vec<(Point, Point)> {
+        OFFSETS
+            .into_iter()
+            .filter_map(|offset| Some((offset, self.step(p, offset)?)))
+            .filter(|&(_, p2)| self[p2].is_passable())
+            .collect()
+    }
+
+    // 通道的端点：死路、路口，或者孤立的位置
+    fn is_stats_node(&self, p: Point) -> bool {
+        self[p] != MapValue::Crossing && self.stats_roads(p).len() != 2
+    }
+
+    // 从 p 出发沿 offset 方向走到下一个端点，返回端点和长度
+    fn stats_walk(&self, p: Point, offset: Point) -> Option<(Point, usize)> {
+        let (mut offset, mut q) = (offset, self.step(p, offset)?);
+        let mut length = 1;
+        while !self.is_stats_node(q) {
+            // 交叉点直行，其他位置转向唯一没走过的方向
+            if self[q] != MapValue::Crossing {
+                offset = self
+                    .stats_roads(q)
+                    .into_iter()
+                    .map(|(o, _)| o)
+                    .find(|&o| o != Point(0, 0) - offset)?;
+            }
+            q = self.step(q, offset)?;
+            length += 1;
+            // 没有端点的环
+            if q == p {
+                return None;
+            }
+        }
+        Some((q, length))
+    }
+
+    pub fn stats(&self) -> MazeResult<MazeStats> {
+        let solution = self.solve(self.st)?;
+        let mut stats = MazeStats {
+            solution_length: solution.len() - 1,
+            ..Default::default()
+        };
+
+        let (mut corridors, mut corridor_total) = (0, 0);
+        let (mut dead_end_total, mut dead_end_count) = (0, 0);
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                let p = Point(i, j);
+                if !self[p].is_passable() {
+                    continue;
+                }
+                stats.cells += 1;
+                if !self.is_stats_node(p) {
+                    continue;
+                }
+                let roads = self.stats_roads(p);
+                match roads.len() {
+                    1 => stats.dead_ends += 1,
+                    n if n >= 3 => {
+                        stats.junctions += 1;
+                        stats.branches += n - 2;
+                    }
+                    _ => {}
+                }
+                for (offset, _) in roads.iter().copied() {
+                    let Some((q, length)) = self.stats_walk(p, offset) else {
+                        continue;
+                    };
+                    // 每条通道从两端各走一次
+                    corridors += 1;
+                    corridor_total += length;
+                    if roads.len() == 1 && self.stats_roads(q).len() != 1 {
+                        dead_end_count += 1;
+                        dead_end_total += length;
+                    }
+                }
+            }
+        }
+
+        let ratio = |a: usize, b: usize| if b == 0 { 0.0 } else { a as f64 / b as f64 };
+        stats.corridor_length = ratio(corridor_total, corridors);
+        stats.river = ratio(dead_end_total, dead_end_count);
+        stats.solution_fraction = ratio(solution.len(), stats.cells);
+        // 环绕时起点和终点之间可以绕过接缝
+        let distance = self.wrapped_distance(self.st, self.ed);
+        stats.tortuosity = ratio(stats.solution_length, distance);
+
+        let offsets: Vec<Option<Point>> = solution
+            .windows(2)
+            .map(|w| {
+                OFFSETS
+                    .into_iter()
+                    .find(|&o| self.step(w[0], o) == Some(w[1]))
+            })
+            .collect();
+        stats.turns = offsets.windows(2).filter(|w| w[0] != w[1]).count();
+        stats.decisions = solution[..solution.len() - 1]
+            .iter()
+            .filter(|&&p| self[p] != MapValue::Crossing && self.stats_roads(p).len() >= 3)
+            .count();
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{generator::Generator, grid::Grid, maze_options::MazeOptions, wrap::Wrap};
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        for generator in [
+            Generator::Kruskal,
+            Generator::BinaryTree,
+            "weave".parse().unwrap(),
+        ] {
+            let random = StdRng::seed_from_u64(1);
+            let maze = MazeMap::new_with_generator(15, 21, random, generator).unwrap();
+            let stats = maze.stats().unwrap();
+            let solution = maze.solve(maze.st).unwrap();
+            assert_eq!(stats.solution_length, solution.len() - 1);
+            assert!(stats.dead_ends > 0 && stats.junctions > 0);
+            assert!(stats.branches >= stats.junctions);
+            assert!(stats.solution_fraction > 0.0 && stats.solution_fraction <= 1.0);
+            assert!(stats.tortuosity >= 1.0);
+            assert!(stats.turns < stats.solution_length);
+            assert!(stats.decisions <= stats.junctions);
+            assert!(stats.corridor_length >= 1.0 && stats.river >= 1.0);
+        }
+
+        // 一条笔直的通道
+        let mut maze =
+            MazeMap::new_with_generator(3, 7, StdRng::seed_from_u64(1), Generator::Kruskal)
+                .unwrap();
+        maze.set_map(Grid::new(3, 7, MapValue::Wall));
+        for j in 0..7 {
+            maze.map_mut()[1][j] = MapValue::Road;
+        }
+        maze.st = Point(1, 0);
+        maze.ed = Point(1, 6);
+        let stats = maze.stats().unwrap();
+        assert_eq!(
+            (stats.solution_length, stats.turns, stats.dead_ends),
+            (6, 0, 2)
+        );
+        assert_eq!((stats.junctions, stats.corridor_length), (0, 6.0));
+        assert_eq!((stats.cells, stats.decisions), (7, 0));
+        assert_eq!((stats.solution_fraction, stats.tortuosity), (1.0, 1.0));
+
+        // 左右相连时绕过接缝的通道更短，起点和终点的距离也按绕过接缝计算
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            wrap: Wrap::Cylinder,
+            ..Default::default()
+        };
+        let mut maze = MazeMap::new_with_options(3, 8, StdRng::seed_from_u64(1), options).unwrap();
+        maze.set_map(Grid::new(3, 8, MapValue::Wall));
+        for j in 0..8 {
+            maze.map_mut()[1][j] = MapValue::Road;
+        }
+        maze.st = Point(1, 1);
+        maze.ed = Point(1, 6);
+        let stats = maze.stats().unwrap();
+        assert_eq!(stats.solution_length, 3);
+        assert_eq!(stats.tortuosity, 1.0);
+
+        // 莫比乌斯带穿过接缝时上下翻转
+        let options = MazeOptions {
+            generator: Generator::Kruskal,
+            wrap: Wrap::Mobius,
+            ..Default::default()
+        };
+        let maze = MazeMap::new_with_options(5, 8, StdRng::seed_from_u64(1), options).unwrap();
+        assert_eq!(maze.wrapped_distance(Point(1, 0), Point(3, 7)), 1);
+        assert_eq!(maze.wrapped_distance(Point(1, 0), Point(1, 7)), 3);
+    }
+}
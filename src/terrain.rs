@@ -0,0 +1,73 @@
+use rand::Rng;
+
+use crate::{map_value::MapValue, maze_map::MazeMap, point::Point};
+
+pub const TERRAINS: [MapValue; 3] = [MapValue::Grass, MapValue::Mud, MapValue::Water];
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 按百分比把道路换成随机的地形，起点、终点和交叉点不变
+    pub fn paint_terrain(&mut self, percent: u8) {
+        if percent == 0 {
+            return;
+        }
+        let roads: Vec<Point> = self
+            .map()
+            .points()
+            .filter(|&p| self[p] == MapValue::Road)
+            .collect();
+        for p in roads {
+            if self.random.gen_range(0..100) >= percent {
+                continue;
+            }
+            let terrain = TERRAINS[self.random.gen_range(0..TERRAINS.len())];
+            self.set(p, terrain);
+        }
+    }
+
+    // 沿路径走完的代价，不计起点
+    pub fn path_cost(&self, path: &[Point]) -> usize {
+        path.iter().skip(1).map(|&p| self[p].cost()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::{maze_options::MazeOptions, solver::Solver};
+
+    use super::*;
+
+    #[test]
+    fn test_terrain() {
+        let options = MazeOptions {
+            loops: 50,
+            terrain: 40,
+            ..Default::default()
+        };
+        let random = StdRng::seed_from_u64(3);
+        let maze = MazeMap::new_with_options(31, 41, random, options).unwrap();
+        assert!(TERRAINS
+            .iter()
+            .all(|terrain| maze.map().iter().any(|v| v == terrain)));
+        assert_eq!((maze[maze.st], maze[maze.ed]), (MapValue::St, MapValue::Ed));
+
+        // 代价最小的路径不会比最短路径贵，步数不会比最短路径少
+        let shortest = maze.solve(maze.st).unwrap();
+        let cheapest = maze
+            .solve_by(&Solver::Dijkstra, maze.st, maze.ed)
+            .unwrap()
+            .path;
+        assert!(maze.path_cost(&cheapest) <= maze.path_cost(&shortest));
+        assert!(cheapest.len() >= shortest.len());
+
+        // 在没有地形的迷宫中代价就是步数
+        let random = StdRng::seed_from_u64(3);
+        let plain = MazeMap::new_with_options(31, 41, random, MazeOptions::default()).unwrap();
+        let path = plain.solve(plain.st).unwrap();
+        assert_eq!(plain.path_cost(&path), path.len() - 1);
+    }
+}
@@ -0,0 +1,277 @@
+use std::thread;
+
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    disjoint_set::DisjointSet,
+    errors::{MazeError, MazeResult},
+    generator::{lattice_columns, Generator, MazeGenerator},
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    maze_options::MazeOptions,
+    point::Point,
+    random::{derive_seed, shuffle},
+    wrap::Wrap,
+};
+
+type TMap = Grid<MapValue>;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct TiledOptions {
+    // 每块的边长，以格子为单位，不能小于 2
+    pub tile: usize,
+    // 同时生成的线程数，不影响生成结果
+    pub threads: usize,
+}
+
+impl Default for TiledOptions {
+    fn default() -> Self {
+        Self {
+            tile: 64,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+// 把地图切成若干块，每块用自己的随机数生成器并行生成，再随机打通块之间的墙连成一个完美迷宫
+// 第 k 块的种子由主种子派生，拼接时使用迷宫自己的随机数生成器，所以结果与线程数无关
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Tiled {
+    pub generator: Generator,
+    pub seed: u64,
+    pub options: TiledOptions,
+}
+
+impl Tiled {
+    pub fn new(generator: Generator, seed: u64, options: TiledOptions) -> Self {
+        Self {
+            generator,
+            seed,
+            options,
+        }
+    }
+
+    // 按格子切分 [0, n)，最后不足 2 个格子的部分并入前一块
+    fn split(n: usize, tile: usize) -> Vec<usize> {
+        let mut starts: Vec<usize> = (0..n).step_by(tile).collect();
+        if starts.len() > 1 && n - starts[starts.len() - 1] < 2 {
+            starts.pop();
+        }
+        starts.push(n);
+        starts
+    }
+
+    fn generate_tile<Random>(&self, index: usize, row: usize, column: usize) -> MazeResult<TMap>
+    where
+        Random: Rng + SeedableRng,
+    {
+        let random = Random::seed_from_u64(derive_seed(self.seed, index as u64));
+        let options = MazeOptions::new(self.generator);
+        let maze = MazeMap::new_with_options(row, column, random, options)?;
+        let (st, ed) = (maze.st, maze.ed);
+        let mut map = maze.into_map();
+        map[st] = MapValue::Road;
+        map[ed] = MapValue::Road;
+        Ok(map)
+    }
+}
+
+impl<Random> MazeGenerator<Random> for Tiled
+where
+    Random: Rng + SeedableRng,
+{
+    fn generate(&self, maze: &mut MazeMap<Random>) -> MazeResult<()> {
+        // 块内生成的一定是完美迷宫，块之间才能用一棵生成树连起来
+        if let Generator::Dungeon(_) | Generator::Weave(_) = self.generator {
+            return Err(MazeError::Init(format!(
+                "分块生成不支持 {}",
+                self.generator
+            )));
+        }
+        if maze.options.wrap != Wrap::None || maze.options.mask.is_some() {
+            return Err(MazeError::Init(String::from("分块生成不支持环绕和遮罩")));
+        }
+        if self.options.tile < 2 {
+            return Err(MazeError::Init(String::from("块的边长不能小于2")));
+        }
+        let rows = Self::split(maze.row().div_ceil(2), self.options.tile);
+        let columns = Self::split(lattice_columns(maze), self.options.tile);
+        let (tr, tc) = (rows.len() - 1, columns.len() - 1);
+        // 每块在地图上的范围，最后一块包括地图边缘多出来的墙
+        let bound = |starts: &[usize], k: usize, size: usize| {
+            let end = if k + 2 == starts.len() {
+                size
+            } else {
+                starts[k + 1] * 2 - 1
+            };
+            (starts[k] * 2, end)
+        };
+        let tiles: Vec<(usize, usize, usize, usize)> = (0..tr * tc)
+            .map(|k| {
+                let (top, bottom) = bound(&rows, k / tc, maze.row());
+                let (left, right) = bound(&columns, k % tc, maze.column());
+                (top, left, bottom - top, right - left)
+            })
+            .collect();
+
+        let threads = self.options.threads.clamp(1, tiles.len());
+        let mut maps: Vec<Option<MazeResult<TMap>>> = (0..tiles.len()).map(|_| None).collect();
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|t| {
+                    let tiles = &tiles;
+                    scope.spawn(move || {
+                        (t..tiles.len())
+                            .step_by(threads)
+                            .map(|k| {
+                                let (_, _, height, width) = tiles[k];
+                                (k, self.generate_tile::<Random>(k, height, width))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            for handle in handles {
+                for (k, map) in handle.join().expect("tile thread panicked") {
+                    maps[k] = Some(map);
+                }
+            }
+        });
+        for (k, map) in maps.into_iter().enumerate() {
+            let map = map.expect("every tile is generated")?;
+            let (top, left, _, _) = tiles[k];
+            let offset = Point(top as i32, left as i32);
+            for (p, &value) in map.points().zip(map.iter()) {
+                maze.set(p + offset, value);
+            }
+        }
+
+        // 相邻两块之间随机选一个位置打通，打通的边组成块之间的一棵生成树
+        let mut edges = vec![];
+        for k in 0..tr * tc {
+            if k % tc + 1 < tc {
+                edges.push((k, k + 1));
+            }
+            if k / tc + 1 < tr {
+                edges.push((k, k + tc));
+            }
+        }
+        shuffle(&mut maze.random, &mut edges);
+        let mut sets = DisjointSet::new(tr * tc);
+        for (a, b) in edges {
+            if !sets.union(a, b) {
+                continue;
+            }
+            // 只在两侧都是道路的位置打通，回溯生成器的道路不一定在偶数坐标上
+            let (i, j) = (a / tc, a % tc);
+            let (line, offset) = if b == a + 1 {
+                let (top, _, height, _) = tiles[a];
+                let column = columns[j + 1] as i32 * 2 - 1;
+                let line: Vec<Point> = (top..top + height)
+                    .map(|i| Point(i as i32, column))
+                    .collect();
+                (line, Point(0, 1))
+            } else {
+                let (_, left, _, width) = tiles[a];
+                let row = rows[i + 1] as i32 * 2 - 1;
+                let line: Vec<Point> = (left..left + width).map(|j| Point(row, j as i32)).collect();
+                (line, Point(1, 0))
+            };
+            let gates: Vec<Point> = line
+                .into_iter()
+                .filter(|&p| maze[p - offset].is_passable() && maze[p + offset].is_passable())
+                .collect();
+            if gates.is_empty() {
+                return Err(MazeError::Init(String::from("相邻两块之间无法打通")));
+            }
+            let gate = gates[maze.random.gen_range(0..gates.len())];
+            maze.set(gate, MapValue::Road);
+        }
+        Ok(())
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng + SeedableRng,
+{
+    // 分块并行生成，options 中的生成器用于生成每一块
+    // 结果只取决于参数中的种子，但与不分块时生成的迷宫不同，所以不记录种子
+    pub fn new_tiled(
+        row: usize,
+        column: usize,
+        seed: u64,
+        options: MazeOptions,
+        tiled: TiledOptions,
+    ) -> MazeResult<Self> {
+        let generator = Tiled::new(options.generator, seed, tiled);
+        Self::new_by(
+            row,
+            column,
+            Random::seed_from_u64(seed),
+            options,
+            &generator,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn test_tiled() {
+        for generator in [Generator::RecursiveBacktracker, Generator::Kruskal] {
+            for (row, column) in [(41, 57), (40, 56), (3, 30)] {
+                let tiled = |threads| TiledOptions { tile: 6, threads };
+                let options = MazeOptions::new(generator);
+                let maze = MazeMap::<StdRng>::new_tiled(row, column, 9, options.clone(), tiled(1))
+                    .unwrap();
+                // 线程数不影响结果
+                for threads in [2, 5] {
+                    let again = MazeMap::<StdRng>::new_tiled(
+                        row,
+                        column,
+                        9,
+                        options.clone(),
+                        tiled(threads),
+                    )
+                    .unwrap();
+                    assert_eq!(again.map(), maze.map());
+                }
+                // 全部道路连通，且相邻道路的对数比道路数少 1，即没有回路
+                let roads = maze.map().iter().filter(|v| v.is_passable()).count();
+                let links = maze
+                    .map()
+                    .points()
+                    .filter(|&p| maze[p].is_passable())
+                    .flat_map(|p| [p + (0, 1), p + (1, 0)])
+                    .filter(|&q| !maze.is_overrange(q) && maze[q].is_passable())
+                    .count();
+                assert_eq!(links + 1, roads, "{} {}x{}", generator, row, column);
+                let mut seen = Grid::new(row, column, false);
+                let mut queue = VecDeque::from([maze.st]);
+                seen[maze.st] = true;
+                let mut count = 0;
+                while let Some(p) = queue.pop_front() {
+                    count += 1;
+                    for q in maze.neighbours(p) {
+                        if maze[q].is_passable() && !seen[q] {
+                            seen[q] = true;
+                            queue.push_back(q);
+                        }
+                    }
+                }
+                assert_eq!(count, roads);
+            }
+        }
+        let options = MazeOptions::new("weave".parse().unwrap());
+        let tiled = TiledOptions::default();
+        assert!(MazeMap::<StdRng>::new_tiled(21, 21, 1, options, tiled).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+pub mod hex;
+pub mod triangle;
+
+use std::{fmt::Debug, hash::Hash, str::FromStr};
+
+use crate::point::Point;
+
+pub use self::{
+    hex::{Hex, HexDirection},
+    triangle::{Triangle, TriangleDirection},
+};
+
+// 非正方形网格的邻接关系，格子之间直接相连，不再用墙占据坐标
+pub trait Topology: Debug + Default + PartialEq + Eq + Hash + Clone + Copy {
+    type Direction: Debug + PartialEq + Eq + Hash + Clone + Copy + FromStr;
+
+    // 格子 p 可以连通的方向，三角形网格与格子的朝向有关
+    fn directions(&self, p: Point) -> Vec<Self::Direction>;
+    // 不检查越界
+    fn neighbour(&self, p: Point, dir: Self::Direction) -> Point;
+    fn opposite(&self, dir: Self::Direction) -> Self::Direction;
+    // 方向在连通位图中的位置
+    fn bit(&self, dir: Self::Direction) -> u8;
+
+    // is_open 判断格子在某个方向上是否连通，glyph 返回格子中心显示的字符
+    fn render(
+        &self,
+        row: usize,
+        column: usize,
+        is_open: impl Fn(Point, Self::Direction) -> bool,
+        glyph: impl Fn(Point) -> char,
+    ) -> Vec<String>;
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use crate::point::Point;
+
+use super::Topology;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum HexDirection {
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl FromStr for HexDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dir = match s.to_ascii_lowercase().as_str() {
+            "e" | "east" => Self::East,
+            "w" | "west" => Self::West,
+            "ne" | "north-east" => Self::NorthEast,
+            "nw" | "north-west" => Self::NorthWest,
+            "se" | "south-east" => Self::SouthEast,
+            "sw" | "south-west" => Self::SouthWest,
+            _ => return Err(format!("unknown hex direction: {}", s)),
+        };
+        Ok(dir)
+    }
+}
+
+// 尖顶六边形，奇数行向右错开半个格子
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Hex;
+
+impl Topology for Hex {
+    type Direction = HexDirection;
+
+    fn directions(&self, _p: Point) -> Vec<Self::Direction> {
+        vec![
+            HexDirection::East,
+            HexDirection::West,
+            HexDirection::NorthEast,
+            HexDirection::NorthWest,
+            HexDirection::SouthEast,
+            HexDirection::SouthWest,
+        ]
+    }
+
+    fn neighbour(&self, p: Point, dir: Self::Direction) -> Point {
+        // 偶数行的斜向邻居偏左，奇数行偏右
+        let shift = p.0.rem_euclid(2);
+        match dir {
+            HexDirection::East => p + (0, 1),
+            HexDirection::West => p - (0, 1),
+            HexDirection::NorthEast => p + (-1, shift),
+            HexDirection::NorthWest => p + (-1, shift - 1),
+            HexDirection::SouthEast => p + (1, shift),
+            HexDirection::SouthWest => p + (1, shift - 1),
+        }
+    }
+
+    fn opposite(&self, dir: Self::Direction) -> Self::Direction {
+        match dir {
+            HexDirection::East => HexDirection::West,
+            HexDirection::West => HexDirection::East,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+        }
+    }
+
+    fn bit(&self, dir: Self::Direction) -> u8 {
+        1 << dir as u8
+    }
+
+    //  / \ / \
+    // | S |   |
+    //  \ / \ / \
+    //   |   | E |
+    //    \ / \ /
+    fn render(
+        &self,
+        row: usize,
+        column: usize,
+        is_open: impl Fn(Point, Self::Direction) -> bool,
+        glyph: impl Fn(Point) -> char,
+    ) -> Vec<String> {
+        let mut lines = vec![vec![' '; column * 4 + 3]; row * 2 + 1];
+        for i in 0..row {
+            for j in 0..column {
+                let p = Point(i as i32, j as i32);
+                let x = j * 4 + i % 2 * 2;
+                let edges = [
+                    (i * 2, x + 1, HexDirection::NorthWest, '/'),
+                    (i * 2, x + 3, HexDirection::NorthEast, '\\'),
+                    (i * 2 + 1, x, HexDirection::West, '|'),
+                    (i * 2 + 1, x + 4, HexDirection::East, '|'),
+                    (i * 2 + 2, x + 1, HexDirection::SouthWest, '\\'),
+                    (i * 2 + 2, x + 3, HexDirection::SouthEast, '/'),
+                ];
+                for (y, x, dir, c) in edges {
+                    if !is_open(p, dir) {
+                        lines[y][x] = c;
+                    }
+                }
+                lines[i * 2 + 1][x + 2] = glyph(p);
+            }
+        }
+        lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>().trim_end().to_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,112 @@
+use std::str::FromStr;
+
+use crate::point::Point;
+
+use super::Topology;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TriangleDirection {
+    East,
+    West,
+    North,
+    South,
+}
+
+impl FromStr for TriangleDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dir = match s.to_ascii_lowercase().as_str() {
+            "e" | "east" => Self::East,
+            "w" | "west" => Self::West,
+            "n" | "north" => Self::North,
+            "s" | "south" => Self::South,
+            _ => return Err(format!("unknown triangle direction: {}", s)),
+        };
+        Ok(dir)
+    }
+}
+
+// 行列之和为偶数的格子尖朝上，只能向下穿过底边；尖朝下的格子只能向上
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct Triangle;
+
+impl Triangle {
+    pub fn is_upward(&self, p: Point) -> bool {
+        (p.0 + p.1).rem_euclid(2) == 0
+    }
+}
+
+impl Topology for Triangle {
+    type Direction = TriangleDirection;
+
+    fn directions(&self, p: Point) -> Vec<Self::Direction> {
+        let vertical = if self.is_upward(p) {
+            TriangleDirection::South
+        } else {
+            TriangleDirection::North
+        };
+        vec![TriangleDirection::East, TriangleDirection::West, vertical]
+    }
+
+    fn neighbour(&self, p: Point, dir: Self::Direction) -> Point {
+        match dir {
+            TriangleDirection::East => p + (0, 1),
+            TriangleDirection::West => p - (0, 1),
+            TriangleDirection::North => p - (1, 0),
+            TriangleDirection::South => p + (1, 0),
+        }
+    }
+
+    fn opposite(&self, dir: Self::Direction) -> Self::Direction {
+        match dir {
+            TriangleDirection::East => TriangleDirection::West,
+            TriangleDirection::West => TriangleDirection::East,
+            TriangleDirection::North => TriangleDirection::South,
+            TriangleDirection::South => TriangleDirection::North,
+        }
+    }
+
+    fn bit(&self, dir: Self::Direction) -> u8 {
+        1 << dir as u8
+    }
+
+    // 尖朝上的格子两边画成 `/` 和 `\`，底边画在下一行；尖朝下的格子相反，顶边画在上一行
+    fn render(
+        &self,
+        row: usize,
+        column: usize,
+        is_open: impl Fn(Point, Self::Direction) -> bool,
+        glyph: impl Fn(Point) -> char,
+    ) -> Vec<String> {
+        let mut lines = vec![vec![' '; column * 2 + 1]; row * 2 + 1];
+        for i in 0..row {
+            for j in 0..column {
+                let p = Point(i as i32, j as i32);
+                let up = self.is_upward(p);
+                let x = j * 2;
+                let (left, right) = if up { ('/', '\\') } else { ('\\', '/') };
+                if !is_open(p, TriangleDirection::West) {
+                    lines[i * 2 + 1][x] = left;
+                }
+                if !is_open(p, TriangleDirection::East) {
+                    lines[i * 2 + 1][x + 2] = right;
+                }
+                // 底边画在格子下方，顶边画在格子上方
+                let (y, dir) = if up {
+                    (i * 2 + 2, TriangleDirection::South)
+                } else {
+                    (i * 2, TriangleDirection::North)
+                };
+                if !is_open(p, dir) {
+                    lines[y][x + 1] = '-';
+                }
+                lines[i * 2 + 1][x + 1] = glyph(p);
+            }
+        }
+        lines
+            .into_iter()
+            .map(|line| line.into_iter().collect::<String>().trim_end().to_owned())
+            .collect()
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use rand::{rngs::ThreadRng, Rng};
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    generator::Generator,
+    grid::Grid,
+    map_value::MapValue,
+    maze_map::MazeMap,
+    maze_options::MazeOptions,
+    move_status::MoveStatus,
+    point::Point,
+    random::randarray,
+    wrap::Wrap,
+};
+
+type TMap = Grid<MapValue>;
+
+// 楼层和楼层内的位置
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct TowerPoint(pub usize, pub Point);
+
+impl Display for TowerPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}F {}", self.0 + 1, self.1)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct TowerOptions {
+    // 相邻两层之间的楼梯数量，1 时整座塔仍然是完美迷宫
+    pub stairs: usize,
+    pub maze: MazeOptions,
+}
+
+impl Default for TowerOptions {
+    fn default() -> Self {
+        Self {
+            stairs: 1,
+            maze: MazeOptions::default(),
+        }
+    }
+}
+
+impl TowerOptions {
+    pub fn new(maze: MazeOptions) -> Self {
+        Self {
+            maze,
+            ..Default::default()
+        }
+    }
+}
+
+// 多层迷宫，每层都是独立生成的平面迷宫，相邻两层通过楼梯连接
+// 起点在最底层，终点在最顶层
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct TowerMap<Random = ThreadRng>
+where
+    Random: Rng,
+{
+    pub random: Random,
+    pub floors: Vec<TMap>,
+    pub st: TowerPoint,
+    pub ed: TowerPoint,
+    pub options: TowerOptions,
+}
+
+impl<Random> TowerMap<Random>
+where
+    Random: Rng,
+{
+    pub fn new_with_random(
+        floors: usize,
+        row: usize,
+        column: usize,
+        random: Random,
+    ) -> MazeResult<Self> {
+        Self::new_with_options(floors, row, column, random, TowerOptions::default())
+    }
+
+    pub fn new_with_options(
+        floors: usize,
+        row: usize,
+        column: usize,
+        random: Random,
+        options: TowerOptions,
+    ) -> MazeResult<Self> {
+        let mut self_ = Self {
+            random,
+            floors: vec![],
+            st: Default::default(),
+            ed: Default::default(),
+            options,
+        };
+        self_.generate(floors, row, column)?;
+        Ok(self_)
+    }
+
+    pub fn generate(&mut self, floors: usize, row: usize, column: usize) -> MazeResult<()> {
+        if floors == 0 {
+            return Err(MazeError::Init(String::from("层数不能小于1")));
+        }
+        if self.options.stairs == 0 {
+            return Err(MazeError::Init(String::from("楼梯数量不能为0")));
+        }
+        // 交叉点需要记录穿过的方向，塔里暂不支持
+        if let Generator::Weave(_) = self.options.maze.generator {
+            return Err(MazeError::Init(String::from("多层迷宫不支持交叉点")));
+        }
+        if self.options.maze.wrap != Wrap::None {
+            return Err(MazeError::Init(String::from("多层迷宫不支持环绕")));
+        }
+        if self.options.maze.frame {
+            return Err(MazeError::Init(String::from("多层迷宫不支持边框")));
+        }
+        self.floors.clear();
+        for f in 0..floors {
+            let maze = MazeMap::new_with_options(
+                row,
+                column,
+                &mut self.random,
+                self.options.maze.clone(),
+            )?;
+            let (st, ed) = (maze.st, maze.ed);
+            let mut map = maze.into_map();
+            if f == 0 {
+                self.st = TowerPoint(f, st);
+            } else {
+                map[st] = MapValue::Road;
+            }
+            if f + 1 == floors {
+                self.ed = TowerPoint(f, ed);
+            } else {
+                map[ed] = MapValue::Road;
+            }
+            self.floors.push(map);
+        }
+        self.place_stairs()
+    }
+
+    pub fn re_generate(&mut self) -> MazeResult<()> {
+        self.generate(self.floor_count(), self.row(), self.column())
+    }
+
+    fn place_stairs(&mut self) -> MazeResult<()> {
+        // 地形上也可以放楼梯，交叉点只能直行，不能放楼梯
+        let is_stair_candidate = |value: MapValue| {
+            value.is_passable()
+                && !matches!(
+                    value,
+                    MapValue::St
+                        | MapValue::Ed
+                        | MapValue::Crossing
+                        | MapValue::StairsUp
+                        | MapValue::StairsDown
+                )
+        };
+        for f in 0..self.floor_count() - 1 {
+            let mut candidates = vec![];
+            for i in 0..self.row() as i32 {
+                for j in 0..self.column() as i32 {
+                    let p = Point(i, j);
+                    if is_stair_candidate(self.floors[f][p])
+                        && is_stair_candidate(self.floors[f + 1][p])
+                    {
+                        candidates.push(p);
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                return Err(MazeError::Init(String::from(
+                    "相邻两层没有可以放置楼梯的位置",
+                )));
+            }
+            let candidates = randarray(&mut self.random, &candidates);
+            for p in candidates.into_iter().take(self.options.stairs) {
+                self.floors[f][p] = MapValue::StairsUp;
+                self.floors[f + 1][p] = MapValue::StairsDown;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn floor_count(&self) -> usize {
+        self.floors.len()
+    }
+
+    pub fn row(&self) -> usize {
+        self.floors[0].row()
+    }
+
+    pub fn column(&self) -> usize {
+        self.floors[0].column()
+    }
+
+    pub fn floor(&self, f: usize) -> &TMap {
+        &self.floors[f]
+    }
+
+    pub fn is_overrange(&self, p: TowerPoint) -> bool {
+        let TowerPoint(f, Point(i, j)) = p;
+        if i < 0 || j < 0 {
+            return true;
+        }
+        f >= self.floor_count() || i as usize >= self.row() || j as usize >= self.column()
+    }
+
+    fn is_road(&self, p: TowerPoint) -> bool {
+        !self.is_overrange(p) && self[p].is_passable()
+    }
+
+    // 同层相邻的道路，以及楼梯连接的上下层
+    pub fn get_roads(&self, p: TowerPoint) -> Vec<TowerPoint> {
+        let mut res: Vec<TowerPoint> =
+            p.1.get_range_vec()
+                .into_iter()
+                .map(|p2| TowerPoint(p.0, p2))
+                .filter(|&p2| self.is_road(p2))
+                .collect();
+        for status in [MoveStatus::Upstairs, MoveStatus::Downstairs] {
+            if let Some(p2) = self.climb(p, status) {
+                res.push(p2);
+            }
+        }
+        res
+    }
+
+    // 站在对应的楼梯上时返回上下层的位置
+    pub fn climb(&self, p: TowerPoint, status: MoveStatus) -> Option<TowerPoint> {
+        if self.is_overrange(p) {
+            return None;
+        }
+        match (self[p], status) {
+            (MapValue::StairsUp, MoveStatus::Upstairs) => Some(TowerPoint(p.0 + 1, p.1)),
+            (MapValue::StairsDown, MoveStatus::Downstairs) => Some(TowerPoint(p.0 - 1, p.1)),
+            _ => None,
+        }
+    }
+
+    fn move_find_road(&self, p: TowerPoint, lp: TowerPoint) -> Option<TowerPoint> {
+        // 楼梯和岔路一样需要玩家做决定
+        if matches!(self[p], MapValue::StairsUp | MapValue::StairsDown) {
+            return None;
+        }
+        let mut res = None;
+        for p2 in self.get_roads(p) {
+            if p2 == lp {
+                continue;
+            }
+            if res.is_some() {
+                return None;
+            }
+            res = Some(p2);
+        }
+        res
+    }
+
+    // 与 `Game::move_to` 相同，沿着没有岔路的通道一直走，遇到楼梯时停下
+    pub fn move_from(&self, pos: TowerPoint, status: MoveStatus) -> MazeResult<Vec<TowerPoint>> {
+        if status.is_stairs() {
+            return match self.climb(pos, status) {
+                Some(p) => Ok(vec![pos, p]),
+                None => Err(MazeError::CanNotMove),
+            };
+        }
+        let mut lp = pos;
+        let mut p = TowerPoint(pos.0, status.get_next(pos.1));
+        if !self.is_road(p) {
+            return Err(MazeError::CanNotMove);
+        }
+        let mut move_list = vec![lp, p];
+        while let Some(next) = self.move_find_road(p, lp) {
+            if p == self.ed {
+                break;
+            }
+            lp = p;
+            p = next;
+            move_list.push(p);
+        }
+        Ok(move_list)
+    }
+
+    pub fn solve(&self, pos: TowerPoint) -> MazeResult<Vec<TowerPoint>> {
+        if self.is_overrange(pos) {
+            return Err(MazeError::SolveException);
+        }
+        let mut map_temp = vec![Grid::new(self.row(), self.column(), None); self.floor_count()];
+        let mut queue = VecDeque::from([pos]);
+        map_temp[pos.0][pos.1] = Some(pos);
+        while let Some(p) = queue.pop_front() {
+            if p == self.ed {
+                let mut res = vec![p];
+                let mut rp = p;
+                while rp != pos {
+                    rp = map_temp[rp.0][rp.1].unwrap();
+                    res.push(rp);
+                }
+                res.reverse();
+                return Ok(res);
+            }
+            for road in self.get_roads(p) {
+                if map_temp[road.0][road.1].is_none() {
+                    map_temp[road.0][road.1] = Some(p);
+                    queue.push_back(road);
+                }
+            }
+        }
+        Err(MazeError::QueueEmpty)
+    }
+}
+
+impl<Random> TowerMap<Random>
+where
+    Random: Rng + Default,
+{
+    pub fn new(floors: usize, row: usize, column: usize) -> MazeResult<Self> {
+        Self::new_with_random(floors, row, column, Random::default())
+    }
+}
+
+impl<Random> Index<TowerPoint> for TowerMap<Random>
+where
+    Random: Rng,
+{
+    type Output = MapValue;
+
+    fn index(&self, index: TowerPoint) -> &Self::Output {
+        &self.floors[index.0][index.1]
+    }
+}
+
+impl<Random> IndexMut<TowerPoint> for TowerMap<Random>
+where
+    Random: Rng,
+{
+    fn index_mut(&mut self, index: TowerPoint) -> &mut Self::Output {
+        &mut self.floors[index.0][index.1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_tower_solve() {
+        for seed in 0..5 {
+            let random = StdRng::seed_from_u64(seed);
+            let tower = TowerMap::new_with_random(4, 11, 15, random).unwrap();
+            assert_eq!(tower.st.0, 0);
+            assert_eq!(tower.ed.0, 3);
+            let path = tower.solve(tower.st).unwrap();
+            assert_eq!(*path.last().unwrap(), tower.ed);
+            // 每一步要么在同层相邻，要么沿楼梯换层
+            for w in path.windows(2) {
+                let (a, b) = (w[0], w[1]);
+                if a.0 == b.0 {
+                    assert!(a.1.get_range_vec().contains(&b.1));
+                } else {
+                    assert_eq!(a.1, b.1);
+                    let status = if b.0 > a.0 {
+                        MoveStatus::Upstairs
+                    } else {
+                        MoveStatus::Downstairs
+                    };
+                    assert_eq!(tower.move_from(a, status).unwrap(), vec![a, b]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tower_options() {
+        let random = StdRng::seed_from_u64(1);
+        let options = TowerOptions {
+            stairs: 3,
+            ..Default::default()
+        };
+        let tower = TowerMap::new_with_options(3, 11, 15, random, options).unwrap();
+        for f in 0..2 {
+            let count = |value| tower.floors[f].iter().filter(|&&v| v == value).count();
+            assert_eq!(count(MapValue::StairsUp), 3);
+            assert_eq!(count(MapValue::St), if f == 0 { 1 } else { 0 });
+        }
+        let random = StdRng::seed_from_u64(1);
+        assert!(TowerMap::new_with_random(0, 11, 15, random).is_err());
+
+        // 全部道路铺上地形后仍然可以放置楼梯
+        for seed in 0..5 {
+            let options = TowerOptions {
+                stairs: 2,
+                maze: MazeOptions {
+                    terrain: 100,
+                    ..Default::default()
+                },
+            };
+            let random = StdRng::seed_from_u64(seed);
+            let tower = TowerMap::new_with_options(3, 11, 15, random, options).unwrap();
+            let path = tower.solve(tower.st).unwrap();
+            assert_eq!(*path.last().unwrap(), tower.ed);
+        }
+    }
+}
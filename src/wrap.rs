@@ -0,0 +1,165 @@
+use std::{collections::HashSet, fmt::Display, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    errors::{MazeError, MazeResult},
+    maze_map::MazeMap,
+    point::Point,
+};
+
+// 地图边缘的连接方式
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum Wrap {
+    #[default]
+    None,
+    // 左右相连
+    Cylinder,
+    // 左右、上下都相连
+    Torus,
+    // 左右相连，穿过时上下翻转
+    Mobius,
+}
+
+impl Wrap {
+    pub fn is_horizontal(&self) -> bool {
+        !matches!(self, Self::None)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Self::Torus)
+    }
+}
+
+impl Display for Wrap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Cylinder => write!(f, "cylinder"),
+            Self::Torus => write!(f, "torus"),
+            Self::Mobius => write!(f, "mobius"),
+        }
+    }
+}
+
+impl FromStr for Wrap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wrap = match s.to_ascii_lowercase().as_str() {
+            "none" => Self::None,
+            "cylinder" => Self::Cylinder,
+            "torus" => Self::Torus,
+            "mobius" | "möbius" => Self::Mobius,
+            _ => return Err(format!("unknown wrap: {}", s)),
+        };
+        Ok(wrap)
+    }
+}
+
+impl<Random> MazeMap<Random>
+where
+    Random: Rng,
+{
+    // 把越过边缘的坐标换算回地图内，无法换算时返回 None
+    pub fn wrap(&self, p: Point) -> Option<Point> {
+        let (row, column) = (self.row() as i32, self.column() as i32);
+        let Point(mut i, mut j) = p;
+        let wrap = self.options.wrap;
+        if wrap.is_horizontal() {
+            // 莫比乌斯带每绕一圈翻转一次
+            if wrap == Wrap::Mobius && j.div_euclid(column) % 2 != 0 {
+                i = row - 1 - i;
+            }
+            j = j.rem_euclid(column);
+        }
+        if wrap.is_vertical() {
+            i = i.rem_euclid(row);
+        }
+        let p = Point(i, j);
+        if self.is_overrange(p) {
+            return None;
+        }
+        Some(p)
+    }
+
+    pub fn step(&self, p: Point, offset: Point) -> Option<Point> {
+        self.wrap(p + offset)
+    }
+
+    // 考虑环绕的曼哈顿距离，可以绕过接缝时取较短的一边，莫比乌斯带穿过接缝时上下翻转
+    pub fn wrapped_distance(&self, a: Point, b: Point) -> usize {
+        let (row, column) = (self.row() as i32, self.column() as i32);
+        let wrap = self.options.wrap;
+        let (di, dj) = ((a.0 - b.0).abs(), (a.1 - b.1).abs());
+        let direct = if wrap.is_vertical() {
+            di.min(row - di)
+        } else {
+            di
+        };
+        if !wrap.is_horizontal() {
+            return (direct + dj) as usize;
+        }
+        let across = column - dj;
+        let flipped = if wrap == Wrap::Mobius {
+            (row - 1 - a.0 - b.0).abs()
+        } else {
+            direct
+        };
+        (direct + dj).min(flipped + across) as usize
+    }
+
+    // 考虑环绕后的四个相邻位置
+    pub fn neighbours(&self, p: Point) -> Vec<Point> {
+        p.get_range_vec()
+            .into_iter()
+            .filter_map(|p2| self.wrap(p2))
+            .collect()
+    }
+
+    // 格子布局在接缝两侧仍然要对齐：左右相连时列数为偶数，上下相连时行数为偶数，
+    // 莫比乌斯带翻转后格子行要落在格子行上，行数必须为奇数
+    pub(crate) fn check_wrap(&self) -> MazeResult<()> {
+        let wrap = self.options.wrap;
+        if wrap.is_horizontal() && !self.column().is_multiple_of(2) {
+            return Err(MazeError::Init(String::from("左右相连时列数必须为偶数")));
+        }
+        if wrap.is_vertical() && !self.row().is_multiple_of(2) {
+            return Err(MazeError::Init(String::from("上下相连时行数必须为偶数")));
+        }
+        if wrap == Wrap::Mobius && self.row().is_multiple_of(2) {
+            return Err(MazeError::Init(String::from("莫比乌斯带的行数必须为奇数")));
+        }
+        Ok(())
+    }
+
+    // 穿过边缘的通道，返回离开时和重新进入时所在的地图外坐标
+    // 例如圆柱左边第 i 行离开的通道记为 (Point(i, -1), Point(i, column))
+    pub fn seams(&self) -> Vec<(Point, Point)> {
+        let mut seen = HashSet::new();
+        let mut res = vec![];
+        for i in 0..self.row() as i32 {
+            for j in 0..self.column() as i32 {
+                let p = Point(i, j);
+                if !self[p].is_passable() {
+                    continue;
+                }
+                for out in p.get_range_vec() {
+                    if !self.is_overrange(out) {
+                        continue;
+                    }
+                    let q = match self.wrap(out) {
+                        Some(q) if self[q].is_passable() => q,
+                        _ => continue,
+                    };
+                    let back = q - (out - p);
+                    if seen.insert((back, out)) {
+                        seen.insert((out, back));
+                        res.push((out, back));
+                    }
+                }
+            }
+        }
+        res
+    }
+}